@@ -32,6 +32,8 @@ class Fluid2d:
         self.x = np.stack([i,j],-1)
         # 基本変数
         self.q = np.zeros((Ni,Nj,4))
+        # 受動スカラー
+        self.phi = np.zeros((Ni,Nj,0))
         # パラメータ
         rhoU = 0.5 # rhoU < rhoD
         rhoD = 1.
@@ -62,6 +64,65 @@ class Fluid2d:
         self.q[:,N0:,2] = 0.
         self.q[:,N0:,3] = eU
     #
+    #------------等エントロピー渦の初期条件----------------
+    #引数：
+    #Ma=一様流（x 方向、流速 1）のマッハ数
+    #beta=渦の強さ（一様流速との比）
+    #R=渦の半径
+    #KH 不安定と同じ格子の中心 (0.5,0) に渦を置いて初期条件を上書きする
+    #低マッハ数補正の検証用（Ma=0.01 など）
+    #-----------------------------------------------
+    def init_isentropic_vortex(self, Ma=0.01, beta=0.5, R=0.1):
+        gam = self.gam
+        p0  = 1./gam/Ma/Ma
+        xc  = 0.5
+        yc  = 0.
+        r2  = ((self.x[:,:,0]-xc)**2 + (self.x[:,:,1]-yc)**2)/R/R
+        f   = np.exp(0.5*(1.-r2))
+        u   = 1. - beta*(self.x[:,:,1]-yc)/R*f
+        v   = beta*(self.x[:,:,0]-xc)/R*f
+        temp = 1. - 0.5*(gam-1.)*Ma*Ma*beta*beta*f*f
+        rho = temp**(1./(gam-1.))
+        p   = p0*temp**(gam/(gam-1.))
+        self.q[:,:,0] = rho
+        self.q[:,:,1] = u
+        self.q[:,:,2] = v
+        self.q[:,:,3] = p/(gam-1.)+0.5*rho*(u*u+v*v)
+    #
+    #------------2 種類の気体の KH 不安定の初期条件----------------
+    #引数：
+    #gamma_sp=各化学種の比熱比（化学種 0, 1 の順）
+    #cv_sp=各化学種の定積比熱
+    #KH 不安定の初期条件の上側の層を化学種 1 に置き換え、圧力を保って全エネルギーを計算し直す
+    #化学種 1 の質量分率は 1 個目の受動スカラーとなる（NS >= 1, NSP = 2 で解く）
+    #-----------------------------------------------
+    def init_kh_two_gases(self, gamma_sp=(1.4,5./3.), cv_sp=(1.,4.34)):
+        rho = self.q[:,:,0]
+        ke  = 0.5*rho*(self.q[:,:,1]**2+self.q[:,:,2]**2)
+        p   = (self.gam-1.)*(self.q[:,:,3]-ke)
+        y   = np.where(self.x[:,:,1]<0., 0., 1.)
+        cv  = (1.-y)*cv_sp[0] + y*cv_sp[1]
+        gam = ((1.-y)*gamma_sp[0]*cv_sp[0] + y*gamma_sp[1]*cv_sp[1])/cv
+        self.q[:,:,3] = p/(gam-1.)+ke
+        self.phi = y[:,:,np.newaxis]
+    #
+    #------------水の衝撃波管問題の初期条件----------------
+    #引数：
+    #gamma=stiffened gas の比熱比
+    #p_inf=stiffened gas の stiffness 圧力
+    #p_l, p_r=高圧側・低圧側の圧力（密度はともに 1）
+    #KH 不安定と同じ格子で、0.25 <= x < 0.75 を高圧側とする（i 方向に周期的なので界面は 2 つ）
+    #1000 kg/m^3, 1 GPa で無次元化すれば水は gamma=4.4, p_inf=0.6
+    #StiffenedGasEoS で解く
+    #-----------------------------------------------
+    def init_water_shock_tube(self, gamma=4.4, p_inf=0.6, p_l=1., p_r=1.e-4):
+        x = self.x[:,:,0]
+        p = np.where((x>=0.25)&(x<0.75), p_l, p_r)
+        self.q[:,:,0] = 1.
+        self.q[:,:,1] = 0.
+        self.q[:,:,2] = 0.
+        self.q[:,:,3] = (p+gamma*p_inf)/(gamma-1.)
+    #
     ############## Fortran I/O ############################
     def input_basic_fort(self, name):
         data = np.loadtxt(name)
@@ -101,17 +162,23 @@ class Fluid2d:
     ############## C++ I/O ############################
     def input_basic_cpp(self, name):
         data = np.loadtxt(name)
-        data = data.reshape((4,self.Ni,self.Nj))
+        data = data.reshape((-1,self.Ni,self.Nj))
         self.q[:,:,0] = data[0,:,:]
         self.q[:,:,1] = data[1,:,:]
         self.q[:,:,2] = data[2,:,:]
         self.q[:,:,3] = data[3,:,:]
+        # passive scalars (if any)
+        self.phi = np.moveaxis(data[4:,:,:], 0, -1)
+    # ハイブリッドスキームの衝撃波センサー (Ni, Nj) を読み込んで返す
+    def input_sensor_cpp(self, name):
+        return np.loadtxt(name).reshape((self.Ni,self.Nj))
     def output_basic_cpp(self, name):
         rho = self.q[:,:,0]
         u   = self.q[:,:,1]
         v   = self.q[:,:,2]
         e   = self.q[:,:,3]
-        np.savetxt(name, np.array([rho,u,v,e]).reshape(4*self.Ni*self.Nj))
+        phi = np.moveaxis(self.phi, -1, 0)
+        np.savetxt(name, np.concatenate([np.array([rho,u,v,e]), phi]).reshape((4+phi.shape[0])*self.Ni*self.Nj))
     def output_coordinate_cpp(self, name):
         x = self.x[:,:,0]
         y = self.x[:,:,1]
@@ -121,6 +188,11 @@ class Fluid2d:
         data = data.reshape((2,self.Ni,self.Nj))
         self.x[:,:,0] = data[0,:,:]
         self.x[:,:,1] = data[1,:,:]
+    def output_eos_table_cpp(self, name, rho, e_int, p, c, T):
+        # TabulatedEoS の表: rho, e_int は増加する 1 次元配列、p, c, T は (len(rho), len(e_int)) の配列
+        with open(name, 'w') as f:
+            f.write('%d %d\n' % (len(rho), len(e_int)))
+            np.savetxt(f, np.concatenate([rho, e_int, np.ravel(p), np.ravel(c), np.ravel(T)]))
     def adjust_basic_cpp(self, fname_of_old_coordinate, Ni_old,Nj_old, fname_of_basic):
         c_old = np.loadtxt(fname_of_old_coordinate).reshape((2,Nj_old*Ni_old))
         x_old = c_old[0,:]
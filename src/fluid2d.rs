@@ -3,16 +3,29 @@ use self::{basic_var::BasicVar, coordinate::Coord};
 mod coordinate;
 pub mod basic_var;
 pub mod marching;
-mod eos;
+pub mod eos;
+mod transport;
+mod reference;
+mod conservation;
 pub mod settings;
 
-const NF: usize = 4;
-
-/// struct representing non-viscous ideal gas
+/// number of passive scalars
+/// one for the two species of MixtureEoS with the feature "mixture", the same in builds and tests
+pub const NS: usize = if cfg!(feature = "mixture") { 1 } else { 0 };
+/// number of equations of Euler system
+const NE: usize = 4;
+/// number of equations including passive scalars
+const NF: usize = NE + NS;
+/// number of work arrays for time integration
+const N_REG: usize = 5;
+
+/// struct representing ideal gas
+/// viscous if transport.re > 0, otherwise non-viscous
+/// T should have trait EoS
 /// should have trait Marching
-pub struct IdealGas<const NI: usize, const NJ: usize, const NB: usize> {
-  /// directory for data-output
-  //dir_o: String,
+pub struct IdealGas<const NI: usize, const NJ: usize, const NB: usize, T> {
+  /// directory for data-output, set by initialize
+  dir_o: String,
 
   /// file name for coordinate
   //f_coordinate: String,
@@ -33,44 +46,110 @@ pub struct IdealGas<const NI: usize, const NJ: usize, const NB: usize> {
   eq: EulerEq::<NI,NJ,NB>,
 
   /// equation of state
-  pub eos: IdealEoS,
+  pub eos: T,
+
+  /// transport properties
+  pub transport: TransportProp,
+
+  /// body force
+  pub force: BodyForce,
+
+  /// reference state of nondimensionalization
+  pub reference: Reference,
+
+  /// positivity-preserving limiter
+  pub limiter: PositivityLimiter,
+
+  /// hybrid central/upwind scheme
+  pub hybrid: HybridScheme,
+
+  /// artificial dissipation for the central flux
+  pub artificial: ArtificialDissipation,
 
   /// boundary condition
   bound: Boundary::<NI,NJ,NB>,
 
   // arrays needed for procedures
-  /// for march_ssprk3
-  /// only the partial array [0:NI-2*NB][0:NJ-2*NB][NF] is used actually
-  arr_q0: [[[f64; NF]; NJ]; NI],
-  /// for march_ssprk3
-  /// only the partial array [0:NI-2*NB][0:NJ-2*NB][NF] is used actually
-  arr_q1: [[[f64; NF]; NJ]; NI],
-  /// for march_ssprk3
+  /// work arrays for march_rk
+  /// only the first ones as many as the Runge-Kutta scheme needs are used
   /// only the partial array [0:NI-2*NB][0:NJ-2*NB][NF] is used actually
-  arr_q2: [[[f64; NF]; NJ]; NI],
+  arr_q: [[[[f64; NF]; NJ]; NI]; N_REG],
+  /// physical time step of the previous step of march_dual_time
+  /// 0 before the first step
+  dt_prev: f64,
+  /// basic variables before the step, for retrying the step
+  basic_old: BasicVarHD::<NI,NJ,NB>,
+  /// passive scalars at the start of the step
+  /// eos of mixture is frozen by them during the step (double flux model)
+  phi_frozen: [[[f64; NS]; NJ]; NI],
+  /// stage and cell (i, j) where blow-up is detected first in the last march_rk
+  blow_up: Option<(String, usize, usize)>,
 }
 
-impl<const NI: usize, const NJ: usize, const NB: usize> IdealGas::<NI,NJ,NB> {
+impl<const NI: usize, const NJ: usize, const NB: usize, T> IdealGas::<NI,NJ,NB,T> {
   /// Constructor of IdealGas
-  pub const fn new(gamma: &f64) -> Self {
-    IdealGas::<NI,NJ,NB> {
+  pub const fn new(eos: T, transport: TransportProp, force: BodyForce, reference: Reference, limiter: PositivityLimiter, hybrid: HybridScheme, artificial: ArtificialDissipation) -> Self {
+    IdealGas::<NI,NJ,NB,T> {
+      dir_o: String::new(),
       settings: SetStructEuler::<NI,NJ,NB>::new(),
       coord: GenStructCoord::<NI,NJ,NB>::new(),
       basic: BasicVarHD::<NI,NJ,NB>::new(),
       eq: EulerEq::<NI,NJ,NB>::new(),
-      eos: IdealEoS::new(gamma),
+      eos,
+      transport,
+      force,
+      reference,
+      limiter,
+      hybrid,
+      artificial,
       bound: Boundary::<NI,NJ,NB>::new(),
-      arr_q0: [[[0.0; NF]; NJ]; NI],
-      arr_q1: [[[0.0; NF]; NJ]; NI],
-      arr_q2: [[[0.0; NF]; NJ]; NI],
+      arr_q: [[[[0.0; NF]; NJ]; NI]; N_REG],
+      dt_prev: 0.0,
+      basic_old: BasicVarHD::<NI,NJ,NB>::new(),
+      phi_frozen: [[[0.0; NS]; NJ]; NI],
+      blow_up: None,
     }
   }
 
-  pub fn initialize(&mut self, dir_o: &str, f_coordinate: &str) {
-    self.coord.input(&f_coordinate);
+  /// y is the radial coordinate if axisymmetric
+  /// files in SI units are nondimensionalized by reference
+  pub fn initialize(&mut self, dir_o: &str, f_coordinate: &str, axisymmetric: &bool) {
+    if self.artificial.is_active() && !self.hybrid.is_active() {
+      panic!("Artificial dissipation is added only at faces of the central flux of hybrid scheme, which is not specified.");
+    }
+    self.dir_o = dir_o.to_string();
+    self.coord.axisymmetric = *axisymmetric;
+    self.coord.input(f_coordinate);
+    self.coord.nondimensionalize(&self.reference);
     self.coord.calc_metrices_dx();
     let f_initial = dir_o.to_string() + "b0000000.dat";
     self.basic.input(&f_initial);
+    self.basic.nondimensionalize(&self.reference);
+  }
+}
+
+#[cfg(test)]
+impl<const NI: usize, const NJ: usize, const NB: usize, T> IdealGas::<NI,NJ,NB,T> {
+  /// inviscid IdealGas without body force, limiter, hybrid scheme and artificial dissipation for tests,
+  /// on the grid whose cell points including dummy grids are given by xy(i, j)
+  /// output directory is in the temporary directory, so that blow-up of a test does not write into the crate
+  pub(crate) fn new_for_test(eos: T, xy: impl Fn(usize, usize) -> (f64, f64), axisymmetric: &bool) -> Self {
+    let mut fluid = Self::new(eos, TransportProp::new(&0.0, &0.72, "constant", &1.0, &0.3830, &0.76),
+      BodyForce::new(&0.0, &0.0, &false), Reference::new(&1.0, &1.0, &1.0, &1.0),
+      PositivityLimiter::new(&false, &1.0e-10, &1.0e-10, &false, &1.0e-10, &1.0e-10),
+      HybridScheme::new("none", "none", &0.1, &0.0),
+      ArtificialDissipation::new("none", &0.5, &(1.0 / 64.0), &1.0));
+    let dir_o = std::env::temp_dir().join(format!("fluid2d_test_{}", std::process::id()));
+    std::fs::create_dir_all(&dir_o).unwrap();
+    fluid.dir_o = dir_o.to_str().unwrap().to_string() + "/";
+    fluid.coord.axisymmetric = *axisymmetric;
+    for i in 0..NI {
+      for j in 0..NJ {
+        (fluid.coord.x[i][j], fluid.coord.y[i][j]) = xy(i, j);
+      }
+    }
+    fluid.coord.calc_metrices_dx();
+    fluid
   }
 }
 
@@ -106,6 +185,8 @@ pub struct BasicVarHD<const NI: usize, const NJ: usize, const NB: usize> {
   v: [[f64; NJ]; NI],
   /// total energy per volume
   e: [[f64; NJ]; NI],
+  /// passive scalars advected with the flow
+  phi: [[[f64; NS]; NJ]; NI],
 }
 
 impl<const NI: usize, const NJ: usize, const NB: usize> BasicVarHD::<NI,NJ,NB> {
@@ -116,6 +197,7 @@ impl<const NI: usize, const NJ: usize, const NB: usize> BasicVarHD::<NI,NJ,NB> {
       u: [[0.0; NJ]; NI],
       v: [[0.0; NJ]; NI],
       e: [[0.0; NJ]; NI],
+      phi: [[[0.0; NS]; NJ]; NI],
     }
   }
 }
@@ -134,24 +216,28 @@ pub struct GenStructCoord<const NI: usize, const NJ: usize, const NB: usize> {
   y: [[f64; NJ]; NI],
 
   // quantities derived from coordinate at first
-  /// metrix ix devided by Jacobian
+  /// metrix ix devided by Jacobian, multiplied by radius of the cell point if axisymmetric
   /// only the partial array [0:NI-2*NB+2][0:NJ-2*NB+2] is used actually
   ixs: [[f64; NJ]; NI],
-  /// metrix iy devided by Jacobian
+  /// metrix iy devided by Jacobian, multiplied by radius of the cell point if axisymmetric
   /// only the partial array [0:NI-2*NB+2][0:NJ-2*NB+2] is used actually
   iys: [[f64; NJ]; NI],
-  /// metrix jx devided by Jacobian
+  /// metrix jx devided by Jacobian, multiplied by radius of the cell point if axisymmetric
   /// only the partial array [0:NI-2*NB+2][0:NJ-2*NB+2] is used actually
   jxs: [[f64; NJ]; NI],
-  /// metrix jy devided by Jacobian
+  /// metrix jy devided by Jacobian, multiplied by radius of the cell point if axisymmetric
   /// only the partial array [0:NI-2*NB+2][0:NJ-2*NB+2] is used actually
   jys: [[f64; NJ]; NI],
-  /// inverse of Jacobian
+  /// inverse of Jacobian, multiplied by radius averaged over the 4 cell points around the vertex if axisymmetric
   /// only the partial array [0:NI-1][0:NJ-1] is used actually
   s: [[f64; NJ]; NI],
   /// dx for CFL condition
   /// only the partial array [0:NI-2*NB][0:NJ-2*NB] is used actually
   dx: [[f64; NJ]; NI],
+  /// whether y is the radial coordinate of axisymmetric flow
+  /// if so, metrices and inverse of Jacobian are multiplied by radius,
+  /// so that they are face areas and volumes per radian
+  axisymmetric: bool,
 }
 
 impl<const NI: usize, const NJ: usize, const NB: usize>  GenStructCoord::<NI,NJ,NB> {
@@ -166,6 +252,7 @@ impl<const NI: usize, const NJ: usize, const NB: usize>  GenStructCoord::<NI,NJ,
       jys: [[0.0; NJ]; NI],
       s: [[0.0; NJ]; NI],
       dx: [[0.0; NJ]; NI],
+      axisymmetric: false,
     }
   }
 }
@@ -183,6 +270,23 @@ struct EulerEq<const NI: usize, const NJ: usize, const NB: usize> {
   /// for rhs
   /// only the partial array [0:NI-2*NB][0:NJ-2*NB+1][NF] is used actually
   arr_fj: [[[f64; NF]; NJ]; NI],
+  /// for rhs of mixture, flux for the cell on the right side of the face
+  /// while arr_fi is for the cell on the left side (double flux model)
+  /// only the partial array [0:NI-2*NB+1][0:NJ-2*NB][NF] is used actually
+  arr_fi_r: [[[f64; NF]; NJ]; NI],
+  /// for rhs of mixture, flux for the cell on the right side of the face
+  /// while arr_fj is for the cell on the left side (double flux model)
+  /// only the partial array [0:NI-2*NB][0:NJ-2*NB+1][NF] is used actually
+  arr_fj_r: [[[f64; NF]; NJ]; NI],
+  /// pressure, reconstructed instead of total energy for mixture or well-balanced body force
+  /// also used for the central flux and the shock sensor of hybrid scheme
+  arr_p: [[f64; NJ]; NI],
+  /// shock sensor of hybrid scheme
+  /// only the partial array [NB-1:NI-NB+1][NB-1:NJ-NB+1] is used actually
+  arr_sensor: [[f64; NJ]; NI],
+  /// blending ratio of the reconstructed face states of each cell by positivity limiter, 1 for no limiting
+  /// only the partial array [NB-1:NI-NB+1][NB-1:NJ-NB+1] is used actually
+  arr_theta: [[f64; NJ]; NI],
 }
 
 impl<const NI: usize, const NJ: usize, const NB: usize> EulerEq::<NI,NJ,NB> {
@@ -191,6 +295,11 @@ impl<const NI: usize, const NJ: usize, const NB: usize> EulerEq::<NI,NJ,NB> {
     EulerEq::<NI,NJ,NB> {
       arr_fi: [[[0.0; NF]; NJ]; NI],
       arr_fj: [[[0.0; NF]; NJ]; NI],
+      arr_fi_r: [[[0.0; NF]; NJ]; NI],
+      arr_fj_r: [[[0.0; NF]; NJ]; NI],
+      arr_p: [[0.0; NJ]; NI],
+      arr_sensor: [[0.0; NJ]; NI],
+      arr_theta: [[1.0; NJ]; NI],
     }
   }
 }
@@ -200,17 +309,265 @@ impl<const NI: usize, const NJ: usize, const NB: usize> EulerEq::<NI,NJ,NB> {
 
 
 /// struct representing ideal equation of state
+/// with gas constant R = (gamma - 1) * cv
 /// should have trait EoS
 pub struct IdealEoS {
-  /// specific heat
+  /// specific heat ratio
   gamma: f64,
+  /// specific heat at constant volume
+  cv: f64,
 }
 
 impl IdealEoS {
   /// constructor of IdealEoS
-  const fn new(gamma: &f64) -> Self {
+  pub const fn new(gamma: &f64, cv: &f64) -> Self {
     IdealEoS {
       gamma: *gamma,
+      cv: *cv,
+    }
+  }
+}
+
+
+
+
+
+/// struct representing stiffened gas eos for liquids
+/// p = (gamma - 1) * rho * e_int - gamma * p_inf
+/// with temperature (e_int - p_inf / rho) / cv
+/// used by replacing the eos of IdealGas in the main program
+/// should have trait EoS
+#[allow(dead_code)]
+pub struct StiffenedGasEoS {
+  /// specific heat ratio
+  gamma: f64,
+  /// specific heat at constant volume
+  cv: f64,
+  /// stiffness pressure
+  p_inf: f64,
+}
+
+impl StiffenedGasEoS {
+  /// constructor of StiffenedGasEoS
+  #[allow(dead_code)]
+  pub const fn new(gamma: &f64, cv: &f64, p_inf: &f64) -> Self {
+    StiffenedGasEoS {
+      gamma: *gamma,
+      cv: *cv,
+      p_inf: *p_inf,
+    }
+  }
+}
+
+
+
+
+
+/// struct representing cubic eos of real gases for supercritical flows
+/// p = R T / (1/rho - b) - a alpha(T) / ((1/rho + d1 b) (1/rho + d2 b))
+/// with van der Waals (alpha = 1, d1 = d2 = 0) or Peng-Robinson (d1,2 = 1 -+ sqrt(2)) model,
+/// where a and b are given by the critical point,
+/// and the ideal gas part has specific heat at constant volume cv and gas constant R = (gamma - 1) cv
+/// used by replacing the eos of IdealGas in the main program
+/// should have trait EoS
+#[allow(dead_code)]
+pub struct CubicEoS {
+  /// model of cubic eos, "van_der_Waals" or "Peng_Robinson"
+  model: &'static str,
+  /// specific heat ratio in the ideal gas limit
+  gamma: f64,
+  /// specific heat at constant volume in the ideal gas limit
+  cv: f64,
+  /// critical temperature
+  t_c: f64,
+  /// critical pressure
+  p_c: f64,
+  /// acentric factor, used only by Peng-Robinson
+  omega: f64,
+}
+
+impl CubicEoS {
+  /// constructor of CubicEoS
+  #[allow(dead_code)]
+  pub const fn new(model: &'static str, gamma: &f64, cv: &f64, t_c: &f64, p_c: &f64, omega: &f64) -> Self {
+    CubicEoS {
+      model,
+      gamma: *gamma,
+      cv: *cv,
+      t_c: *t_c,
+      p_c: *p_c,
+      omega: *omega,
+    }
+  }
+}
+
+
+
+
+
+/// struct representing eos given by a table of pressure, sound-speed, temperature
+/// on a grid of density and internal energy per mass, loaded by input
+/// used by replacing the eos of IdealGas in the main program
+/// should have trait EoS
+pub struct TabulatedEoS {
+  /// table loaded by input, shared by the frozen copies
+  table: Option<std::sync::Arc<EoSTable>>,
+  /// interpolation, "bilinear" or "bicubic"
+  interpolation: &'static str,
+}
+
+impl TabulatedEoS {
+  /// constructor of TabulatedEoS
+  /// table should be loaded by input before use
+  #[allow(dead_code)]
+  pub const fn new(interpolation: &'static str) -> Self {
+    TabulatedEoS {
+      table: None,
+      interpolation,
+    }
+  }
+}
+
+/// struct representing table of TabulatedEoS
+struct EoSTable {
+  /// file name of table
+  f_name: String,
+  /// density of grid, increasing
+  rho: Vec<f64>,
+  /// internal energy per mass of grid, increasing
+  e_int: Vec<f64>,
+  /// pressure at [rho][e_int]
+  p: Vec<Vec<f64>>,
+  /// sound-speed at [rho][e_int]
+  cs: Vec<Vec<f64>>,
+  /// temperature at [rho][e_int]
+  temp: Vec<Vec<f64>>,
+  /// numbers of evaluations below/above the range of density and internal energy
+  n_out: [std::sync::atomic::AtomicUsize; 4],
+}
+
+
+
+
+
+/// struct representing mixture of NSP ideal gases
+/// with the composition given by passive scalars,
+/// where the first NSP - 1 passive scalars are mass fractions of species 1, ..., NSP - 1
+/// used by replacing the eos of IdealGas in the main program
+/// should have trait EoS
+pub struct MixtureEoS<const NSP: usize> {
+  /// specific heat ratio of each species
+  gamma_sp: [f64; NSP],
+  /// specific heat at constant volume of each species
+  cv_sp: [f64; NSP],
+  /// ideal gas eos with specific heats of the frozen mixture
+  ideal: IdealEoS,
+}
+
+impl<const NSP: usize> MixtureEoS<NSP> {
+  /// constructor of MixtureEoS
+  /// frozen as pure species 0
+  #[allow(dead_code)]
+  pub const fn new(gamma_sp: &[f64; NSP], cv_sp: &[f64; NSP]) -> Self {
+    assert!(NSP >= 1 && NSP <= NS + 1, "NSP - 1 must not exceed NS.");
+    MixtureEoS {
+      gamma_sp: *gamma_sp,
+      cv_sp: *cv_sp,
+      ideal: IdealEoS::new(&gamma_sp[0], &cv_sp[0]),
+    }
+  }
+}
+
+
+
+
+
+/// struct representing reference state of nondimensionalization in SI units
+/// files of coordinate and basic variables are in SI units,
+/// and nondimensional if all unity
+pub struct Reference {
+  /// reference length
+  pub length: f64,
+  /// reference density
+  pub density: f64,
+  /// reference velocity
+  pub velocity: f64,
+  /// reference temperature
+  pub temperature: f64,
+}
+
+impl Reference {
+  /// constructor of Reference
+  pub const fn new(length: &f64, density: &f64, velocity: &f64, temperature: &f64) -> Self {
+    Reference {
+      length: *length,
+      density: *density,
+      velocity: *velocity,
+      temperature: *temperature,
+    }
+  }
+}
+
+
+
+
+
+/// struct representing transport properties of viscous flow
+/// should have trait Transport
+pub struct TransportProp {
+  /// Reynolds number, non-viscous if 0
+  re: f64,
+  /// Prandtl number
+  pr: f64,
+  /// viscosity law: "constant", "Sutherland" or "power"
+  law: &'static str,
+  /// reference temperature at which viscosity is 1/re
+  t_ref: f64,
+  /// Sutherland temperature devided by t_ref
+  s_suth: f64,
+  /// exponent of power law
+  omega: f64,
+}
+
+impl TransportProp {
+  /// constructor of TransportProp
+  pub const fn new(re: &f64, pr: &f64, law: &'static str,
+    t_ref: &f64, s_suth: &f64, omega: &f64) -> Self {
+    TransportProp {
+      re: *re,
+      pr: *pr,
+      law,
+      t_ref: *t_ref,
+      s_suth: *s_suth,
+      omega: *omega,
+    }
+  }
+}
+
+
+
+
+
+/// struct representing body force
+/// if balanced, the scheme is well-balanced for isothermal hydrostatic equilibrium,
+/// with density and pressure reconstructed as the deviation from the local hydrostatic profile
+/// should have trait Source
+pub struct BodyForce {
+  /// x-component of gravity
+  gx: f64,
+  /// y-component of gravity
+  gy: f64,
+  /// whether the scheme is well-balanced
+  balanced: bool,
+}
+
+impl BodyForce {
+  /// constructor of BodyForce
+  pub const fn new(gx: &f64, gy: &f64, balanced: &bool) -> Self {
+    BodyForce {
+      gx: *gx,
+      gy: *gy,
+      balanced: *balanced,
     }
   }
 }
@@ -228,4 +585,152 @@ impl<const NI: usize, const NJ: usize, const NB: usize> Boundary::<NI,NJ,NB> {
   const fn new() -> Self {
     Boundary::<NI,NJ,NB> {}
   }
-}
\ No newline at end of file
+}
+
+
+
+
+
+/// struct representing control of time step
+pub struct TimeStepControl {
+  /// CFL number
+  cfl_coeff: f64,
+  /// CFL number at the first step, ramped linearly to cfl_coeff
+  cfl_init: f64,
+  /// number of steps for ramping CFL number
+  n_ramp: usize,
+  /// minimum time step, below which the run is stopped
+  dt_min: f64,
+  /// maximum time step
+  dt_max: f64,
+  /// maximum number of retrying a step with halved time step
+  /// when positivity of density/pressure is violated
+  n_retry: usize,
+  /// number of steps taken
+  n_step: usize,
+  /// constraint limiting the last time step: "convective", "diffusive" or "dt_max"
+  pub constraint: &'static str,
+}
+
+impl TimeStepControl {
+  /// constructor of TimeStepControl
+  pub const fn new(cfl_coeff: &f64, cfl_init: &f64, n_ramp: &usize,
+    dt_min: &f64, dt_max: &f64, n_retry: &usize) -> Self {
+    TimeStepControl {
+      cfl_coeff: *cfl_coeff,
+      cfl_init: *cfl_init,
+      n_ramp: *n_ramp,
+      dt_min: *dt_min,
+      dt_max: *dt_max,
+      n_retry: *n_retry,
+      n_step: 0,
+      constraint: "convective",
+    }
+  }
+}
+
+
+
+
+
+/// struct representing positivity-preserving limiter of Zhang-Shu type
+/// reconstructed face states of a cell are pulled toward the cell value by a common ratio
+/// so that density and pressure are above the floors at all the faces of the cell
+/// optionally, density and pressure are clipped to separate floors after updates (not conservative, not part of the limiter)
+pub struct PositivityLimiter {
+  /// limiting reconstructed face states
+  face: bool,
+  /// floor of density of the face states
+  rho_min: f64,
+  /// floor of pressure of the face states
+  p_min: f64,
+  /// clipping updates of explicit Runge-Kutta stages and LU-SGS
+  clip: bool,
+  /// floor of density of clipping
+  rho_clip: f64,
+  /// floor of pressure of clipping
+  p_clip: f64,
+  /// number of cells with limited face states since the last take_counts
+  n_face: usize,
+  /// number of clipped cells since the last take_counts
+  n_clip: usize,
+}
+
+impl PositivityLimiter {
+  /// constructor of PositivityLimiter
+  pub const fn new(face: &bool, rho_min: &f64, p_min: &f64, clip: &bool, rho_clip: &f64, p_clip: &f64) -> Self {
+    PositivityLimiter {
+      face: *face,
+      rho_min: *rho_min,
+      p_min: *p_min,
+      clip: *clip,
+      rho_clip: *rho_clip,
+      p_clip: *p_clip,
+      n_face: 0,
+      n_clip: 0,
+    }
+  }
+}
+
+
+
+
+
+/// struct representing hybrid central/upwind scheme switched by shock sensor
+/// the 4th order central flux of split form is used at faces where the sensor of both adjacent cells is below the threshold,
+/// and the upwind flux by the reconstruction and flux scheme of calc_rhs elsewhere
+/// the central flux is blended with the upwind flux by the ratio of dissipation
+pub struct HybridScheme {
+  /// two-point flux of split form for the central flux, "none" for the upwind flux at all faces
+  central: &'static str,
+  /// shock sensor, "Ducros" or "Jameson", or "none" for the central flux at all faces
+  sensor: &'static str,
+  /// threshold of the shock sensor
+  threshold: f64,
+  /// ratio of the upwind flux blended into the central flux, 0 for no dissipation
+  dissipation: f64,
+}
+
+impl HybridScheme {
+  /// constructor of HybridScheme
+  pub const fn new(central: &'static str, sensor: &'static str, threshold: &f64, dissipation: &f64) -> Self {
+    HybridScheme {
+      central,
+      sensor,
+      threshold: *threshold,
+      dissipation: *dissipation,
+    }
+  }
+}
+
+
+
+
+
+/// struct representing artificial dissipation added at faces of the central flux of hybrid scheme
+/// "JST": 2nd and 4th order dissipation of Jameson, Schmidt and Turkel with pressure sensor
+/// "VNR": artificial bulk viscosity of von Neumann and Richtmyer type, beta = c_vnr * rho * dx^2 * |div u| in compression
+/// "JST_VNR": both, "none": no artificial dissipation
+/// it is added only at faces of the central flux, so hybrid scheme has to be active
+pub struct ArtificialDissipation {
+  /// model of artificial dissipation
+  model: &'static str,
+  /// coefficient of 2nd order dissipation of JST
+  k2: f64,
+  /// coefficient of 4th order dissipation of JST
+  k4: f64,
+  /// coefficient of artificial bulk viscosity
+  c_vnr: f64,
+}
+
+impl ArtificialDissipation {
+  /// constructor of ArtificialDissipation
+  pub const fn new(model: &'static str, k2: &f64, k4: &f64, c_vnr: &f64) -> Self {
+    ArtificialDissipation {
+      model,
+      k2: *k2,
+      k4: *k4,
+      c_vnr: *c_vnr,
+    }
+  }
+}
@@ -3,7 +3,9 @@ use std::io::{Write, BufReader, BufWriter, BufRead, stdout};
 
 pub trait BasicVar {
   fn input(&mut self, f_name: &str);
-  fn output(&self, dir_o: &str, f_settings: &str, t: &f64, tstep: &usize, iter: &usize, cpu_time: &u64, rest_time: &u64);
+  fn output_snapshot(&self, reference: &super::Reference, f_name: &str);
+  #[allow(clippy::too_many_arguments)]
+  fn output(&self, reference: &super::Reference, dir_o: &str, f_settings: &str, t: &f64, tstep: &usize, iter: &usize, inner: &Option<(f64, f64)>, dt_limit: &str, limited: &(usize, usize), cpu_time: &u64, rest_time: &u64);
 }
 
 
@@ -14,51 +16,67 @@ for super::BasicVarHD::<NI,NJ,NB> {
   fn input(&mut self, f_name: &str) {
     let f = File::open(f_name).unwrap();
     let buf = BufReader::new(f);
-    let mut n_line: usize = 0;
-    for line in buf.lines() {
+    for (n_line, line) in buf.lines().enumerate() {
         let l: &str = &line.unwrap();
         let c = n_line / NI / NJ;
         let n_line_sub = n_line - NI * NJ * c;
-        let i = n_line_sub / NI;
-        let j = n_line_sub - NI * i;
+        let i = n_line_sub / NJ;
+        let j = n_line_sub - NJ * i;
         match c {
             0 => self.rho[i][j] = l.trim().parse::<f64>().unwrap(),
             1 => self.u[i][j] = l.trim().parse::<f64>().unwrap(),
             2 => self.v[i][j] = l.trim().parse::<f64>().unwrap(),
             3 => self.e[i][j] = l.trim().parse::<f64>().unwrap(),
+            c if c < 4 + super::NS => self.phi[i][j][c-4] = l.trim().parse::<f64>().unwrap(),
             _ => panic!("Data size of basic var may be wrong."),
         }
-        n_line += 1;
     }
   }
 
 
 
-  fn output(&self, dir_o: &str, f_settings: &str, t: &f64, tstep: &usize, iter: &usize, cpu_time: &u64, rest_time: &u64) {
-    // output into file
-    let mut fo_name = format!("b{:07}.dat", tstep);
-    fo_name = dir_o.to_string() + &fo_name;
-    let mut file = BufWriter::new(File::create(fo_name).unwrap());
+  /// write basic variables into f_name in the same format as the initial condition,
+  /// converted into SI units by reference
+  fn output_snapshot(&self, reference: &super::Reference, f_name: &str) {
+    let mut file = BufWriter::new(File::create(f_name).unwrap());
     for i in 0..NI {
         for j in 0..NJ {
-            write!(file, "{:.18e}\n", self.rho[i][j]).unwrap();
+            writeln!(file, "{:.18e}", self.rho[i][j] * reference.density).unwrap();
         }
     }
     for i in 0..NI {
         for j in 0..NJ {
-            write!(file, "{:.18e}\n", self.u[i][j]).unwrap();
+            writeln!(file, "{:.18e}", self.u[i][j] * reference.velocity).unwrap();
         }
     }
     for i in 0..NI {
       for j in 0..NJ {
-          write!(file, "{:.18e}\n", self.v[i][j]).unwrap();
+          writeln!(file, "{:.18e}", self.v[i][j] * reference.velocity).unwrap();
       }
     }
     for i in 0..NI {
       for j in 0..NJ {
-          write!(file, "{:.18e}\n", self.e[i][j]).unwrap();
+          writeln!(file, "{:.18e}", self.e[i][j] * reference.pressure()).unwrap();
       }
     }
+    for n in 0..self.phi[0][0].len() {
+      for i in 0..NI {
+        for j in 0..NJ {
+          writeln!(file, "{:.18e}", self.phi[i][j][n]).unwrap();
+        }
+      }
+    }
+  }
+
+
+  /// inner is (mean number of inner iterations per physical step, maximum final residual reduction)
+  /// of dual time stepping, added to status if given
+  /// dt_limit is the constraint limiting the time step, added to status if not empty
+  /// limited is (number of cells with limited face states, number of clipped cells) by positivity limiter, added to status if not zero
+  /// basic variables and time are converted into SI units by reference
+  fn output(&self, reference: &super::Reference, dir_o: &str, f_settings: &str, t: &f64, tstep: &usize, iter: &usize, inner: &Option<(f64, f64)>, dt_limit: &str, limited: &(usize, usize), cpu_time: &u64, rest_time: &u64) {
+    let fo_name = dir_o.to_string() + &format!("b{:07}.dat", tstep);
+    self.output_snapshot(reference, &fo_name);
 
     // convert cpu_time and rest_time into h/m/s
     const SECS: u64 = 60;
@@ -73,7 +91,17 @@ for super::BasicVarHD::<NI,NJ,NB> {
     s_r -= m_r * SECS;
 
     // output status
-    let line = format!("elapsed: {h_c:3} h {m_c:02} m {s_c:02} s | tstep = {tstep:5} | t = {t:10.4} | iter = {iter:7} | rest: {h_r:3} h {m_r:02} m {s_r:02} s");
+    let t = t * reference.time();
+    let mut line = format!("elapsed: {h_c:3} h {m_c:02} m {s_c:02} s | tstep = {tstep:5} | t = {t:10.4} | iter = {iter:7} | rest: {h_r:3} h {m_r:02} m {s_r:02} s");
+    if let Some((n_inner, res_inner)) = inner {
+      line += &format!(" | inner = {n_inner:6.1} | inner res = {res_inner:.3e}");
+    }
+    if !dt_limit.is_empty() {
+      line += &format!(" | dt limit: {dt_limit}");
+    }
+    if *limited != (0, 0) {
+      line += &format!(" | limited: face = {}, clip = {}", limited.0, limited.1);
+    }
 
     let mut file = BufWriter::new(OpenOptions::new().append(true).open(f_settings).unwrap());
     writeln!(file, "{}", line).unwrap();
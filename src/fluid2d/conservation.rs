@@ -0,0 +1,122 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use super::eos::EoS;
+
+
+
+impl<const NI: usize, const NJ: usize, const NB: usize, T: EoS> super::IdealGas::<NI,NJ,NB,T> {
+  /// totals over the computational domain integrated with the inverse of Jacobian, in SI units
+  /// mass, x/y momentum, total energy, kinetic energy, enstrophy (integral of vorticity^2 / 2)
+  /// and entropy (integral of rho * cv * ln(p / rho^gamma), exact for ideal gas)
+  /// vorticity by 2nd order central difference
+  /// per unit depth, or per radian if axisymmetric
+  pub fn calc_totals(&self) -> [f64; 7] {
+    let basic = &self.basic;
+    let coord = &self.coord;
+    let mut totals = [0.0; 7];
+    for i in NB..(NI-NB) {
+      for j in NB..(NJ-NB) {
+        let (rho, u, v, e) = (&basic.rho[i][j], &basic.u[i][j], &basic.v[i][j], &basic.e[i][j]);
+        let s_a = 0.25 * (coord.s[i-1][j-1] + coord.s[i][j-1] + coord.s[i-1][j] + coord.s[i][j]);
+        let (im, jm) = (i + 1 - NB, j + 1 - NB);
+        let u_i = 0.5 * (basic.u[i+1][j] - basic.u[i-1][j]);
+        let u_j = 0.5 * (basic.u[i][j+1] - basic.u[i][j-1]);
+        let v_i = 0.5 * (basic.v[i+1][j] - basic.v[i-1][j]);
+        let v_j = 0.5 * (basic.v[i][j+1] - basic.v[i][j-1]);
+        let rot = (coord.ixs[im][jm] * v_i + coord.jxs[im][jm] * v_j - coord.iys[im][jm] * u_i - coord.jys[im][jm] * u_j) / s_a;
+        let eos = self.eos.frozen(&basic.phi[i][j]);
+        let cv = eos.calc_cv(rho, u, v, e) * self.reference.specific_heat();
+        let gamma = eos.calc_cp(rho, u, v, e) / eos.calc_cv(rho, u, v, e);
+        let p = eos.calc_p(rho, u, v, e) * self.reference.pressure();
+        let rho_si = rho * self.reference.density;
+        totals[0] += rho * s_a;
+        totals[1] += rho * u * s_a;
+        totals[2] += rho * v * s_a;
+        totals[3] += e * s_a;
+        totals[4] += 0.5 * rho * (u * u + v * v) * s_a;
+        totals[5] += 0.5 * rot * rot * s_a;
+        totals[6] += rho_si * cv * (p / rho_si.powf(gamma)).ln() * s_a;
+      }
+    }
+    // SI units
+    let r = &self.reference;
+    let volume = if coord.axisymmetric { r.length.powi(3) } else { r.length.powi(2) };
+    totals[0] *= r.density * volume;
+    totals[1] *= r.density * r.velocity * volume;
+    totals[2] *= r.density * r.velocity * volume;
+    totals[3] *= r.pressure() * volume;
+    totals[4] *= r.pressure() * volume;
+    totals[5] *= r.velocity * r.velocity / (r.length * r.length) * volume;
+    totals[6] *= volume;
+    totals
+  }
+
+
+  /// append totals of calc_totals at time t into CSV file f_name
+  /// the file is created with the header if tstep = 0
+  pub fn output_conservation(&self, f_name: &str, t: &f64, tstep: &usize) {
+    let mut file = if *tstep == 0 {
+      let mut file = File::create(f_name).unwrap();
+      writeln!(file, "tstep,t,mass,momentum_x,momentum_y,energy,kinetic_energy,enstrophy,entropy").unwrap();
+      file
+    } else {
+      OpenOptions::new().append(true).open(f_name).unwrap()
+    };
+    let totals = self.calc_totals();
+    write!(file, "{},{:.15e}", tstep, t * self.reference.time()).unwrap();
+    for total in totals {
+      write!(file, ",{:.15e}", total).unwrap();
+    }
+    writeln!(file).unwrap();
+  }
+}
+
+
+
+#[test]
+fn test_totals() {
+  // totals of a uniform state are the state times the volume of the domain in SI units,
+  // the area for planar flow and the volume per radian (integral of r dr dx) for axisymmetric flow
+  const NI: usize = 16;
+  const NJ: usize = 12;
+  const NB: usize = 4;
+  let (dx, dy, y_0) = (0.1, 0.05, 0.5);
+  let (lx, ly) = ((NI - 2 * NB) as f64 * dx, (NJ - 2 * NB) as f64 * dy);
+  let xy = |i: usize, j: usize| ((i as f64 - NB as f64 + 0.5) * dx, y_0 + (j as f64 - NB as f64 + 0.5) * dy);
+  let (rho, u, v, p) = (1.2, 0.3, -0.4, 0.8);
+  let (gamma, cv) = (1.4, 2.5);
+  for axisymmetric in [false, true] {
+    let mut fluid = super::IdealGas::<NI,NJ,NB,_>::new_for_test(super::IdealEoS::new(&gamma, &cv), xy, &axisymmetric);
+    fluid.reference = super::Reference::new(&2.0, &1.5, &3.0, &300.0);
+    let e = fluid.eos.calc_e_wp(&rho, &u, &v, &p);
+    for i in 0..NI {
+      for j in 0..NJ {
+        (fluid.basic.rho[i][j], fluid.basic.u[i][j], fluid.basic.v[i][j], fluid.basic.e[i][j]) = (rho, u, v, e);
+      }
+    }
+    let r = &fluid.reference;
+    let volume = if axisymmetric {
+      lx * 0.5 * ((y_0 + ly).powi(2) - y_0 * y_0) * r.length.powi(3)
+    } else {
+      lx * ly * r.length.powi(2)
+    };
+    let (rho_si, p_si, cv_si) = (rho * r.density, p * r.pressure(), cv * r.specific_heat());
+    let expected = [rho_si, rho_si * u * r.velocity, rho_si * v * r.velocity, e * r.pressure(),
+      0.5 * rho * (u * u + v * v) * r.pressure(), 0.0, rho_si * cv_si * (p_si / rho_si.powf(gamma)).ln()].map(|q| q * volume);
+    let totals = fluid.calc_totals();
+    for k in 0..7 {
+      assert!((totals[k] - expected[k]).abs() < 1.0e-12 * expected[k].abs().max(1.0), "total {} of axisymmetric = {}: {} != {}", k, axisymmetric, totals[k], expected[k]);
+    }
+  }
+  // enstrophy of solid body rotation of angular velocity omega, whose vorticity is 2 omega
+  let mut fluid = super::IdealGas::<NI,NJ,NB,_>::new_for_test(super::IdealEoS::new(&gamma, &cv), xy, &false);
+  let omega = 0.7;
+  for i in 0..NI {
+    for j in 0..NJ {
+      let (x, y) = xy(i, j);
+      (fluid.basic.rho[i][j], fluid.basic.u[i][j], fluid.basic.v[i][j], fluid.basic.e[i][j]) = (rho, -omega * y, omega * x, 2.0);
+    }
+  }
+  let enstrophy = 0.5 * (2.0 * omega).powi(2) * lx * ly;
+  assert!((fluid.calc_totals()[5] - enstrophy).abs() < 1.0e-12 * enstrophy);
+}
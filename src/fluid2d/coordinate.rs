@@ -31,6 +31,24 @@ for super::GenStructCoord::<NI,NJ,NB> {
         self.s[i][j] = ((self.x[1+i][1+j] - self.x[i][j]) * (self.y[i][1+j] - self.y[1+i][j]) - (self.y[1+i][1+j] - self.y[i][j]) * (self.x[i][1+j] - self.x[1+i][j]))  * 0.5;
       }
     }
+    // multiply metrices and inverse of Jacobian by radius for axisymmetric flow
+    // so that they represent face areas and volumes per radian
+    if self.axisymmetric {
+      for i in 0..(NI-2*NB+2) {
+        for j in 0..(NJ-2*NB+2) {
+          let r = self.y[NB-1+i][NB-1+j];
+          self.ixs[i][j] *= r;
+          self.iys[i][j] *= r;
+          self.jxs[i][j] *= r;
+          self.jys[i][j] *= r;
+        }
+      }
+      for i in 0..(NI-1) {
+        for j in 0..(NJ-1) {
+          self.s[i][j] *= 0.25 * (self.y[i][j] + self.y[1+i][j] + self.y[i][1+j] + self.y[1+i][1+j]);
+        }
+      }
+    }
     // calculate dx for CFL condition
     // evaluated at cell-points (i,j)
     // returning (Ni-2*Nb,Nj-2*Nb) array
@@ -62,21 +80,49 @@ for super::GenStructCoord::<NI,NJ,NB> {
   fn input(&mut self, f_name: &str) {
     let f = File::open(f_name).unwrap();
     let buf = BufReader::new(f);
-    let mut n_line: usize = 0;
-    for line in buf.lines() {
+    for (n_line, line) in buf.lines().enumerate() {
         let l: &str = &line.unwrap();
         let c = n_line / NI / NJ;
         let n_line_sub = n_line - NI * NJ * c;
-        let i = n_line_sub / NI;
-        let j = n_line_sub - NI * i;
+        let i = n_line_sub / NJ;
+        let j = n_line_sub - NJ * i;
         match c {
             0 => self.x[i][j] = l.trim().parse::<f64>().unwrap(),
             1 => self.y[i][j] = l.trim().parse::<f64>().unwrap(),
             _ => panic!("Data size of coordinate may be wrong."),
         }
-        n_line += 1;
     }
   }
 
 
-}
\ No newline at end of file
+}
+
+
+
+impl<const NI: usize, const NJ: usize, const NB: usize> super::GenStructCoord::<NI,NJ,NB> {
+  /// whether the j-face between cells (NB+i, NB+j-1) and (NB+i, NB+j) is on the axis of axisymmetric flow
+  /// judged by the radius of the face relative to the distance of the cell points,
+  /// so that the axis is detected regardless of round-off of the coordinate
+  pub(super) fn is_axis_j(&self, i: &usize, j: &usize) -> bool {
+    let (y_l, y_r) = (self.y[NB+i][NB+j-1], self.y[NB+i][NB+j]);
+    self.axisymmetric && (y_l + y_r).abs() <= 1.0e-8 * (y_r - y_l).abs()
+  }
+
+  /// metrices (ixs, iys) at the cell point (i, j) including dummy cells by 4th order central difference,
+  /// consistent with the divergence of the 4th order central flux so that free stream is preserved
+  pub(super) fn metric_i(&self, i: &usize, j: &usize) -> (f64, f64) {
+    let (i, j) = (*i, *j);
+    let d = |q: &[f64; NJ]| 2.0 / 3.0 * (q[j+1] - q[j-1]) - 1.0 / 12.0 * (q[j+2] - q[j-2]);
+    let r = if self.axisymmetric { self.y[i][j] } else { 1.0 };
+    (d(&self.y[i]) * r, -d(&self.x[i]) * r)
+  }
+
+  /// metrices (jxs, jys) at the cell point (i, j) including dummy cells by 4th order central difference,
+  /// consistent with the divergence of the 4th order central flux so that free stream is preserved
+  pub(super) fn metric_j(&self, i: &usize, j: &usize) -> (f64, f64) {
+    let (i, j) = (*i, *j);
+    let d = |q: &[[f64; NJ]; NI]| 2.0 / 3.0 * (q[i+1][j] - q[i-1][j]) - 1.0 / 12.0 * (q[i+2][j] - q[i-2][j]);
+    let r = if self.axisymmetric { self.y[i][j] } else { 1.0 };
+    (-d(&self.y) * r, d(&self.x) * r)
+  }
+}
@@ -1,28 +1,143 @@
-const NF: usize = 4;
+use super::NS;
 
-pub trait EoS {
+mod table;
+
+/// number of equations of Euler system
+const NE: usize = 4;
+
+pub trait EoS: Sized {
+  /// whether the eos depends on the composition given by passive scalars
+  fn is_mixture(&self) -> bool;
+  /// eos with the composition given by passive scalars frozen,
+  /// identical to self if not mixture
+  fn frozen(&self, phi: &[f64; NS]) -> Self;
+  /// description of eos for settings file
+  fn describe(&self) -> String;
+  /// diagnostics of eos reported at the first output where it is not empty and at the end of calculation,
+  /// empty if nothing to report
+  fn diagnostics(&self) -> String {
+    String::new()
+  }
   /// calc pressure from density, velocity, total energy per volume
   fn calc_p(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64;
   /// calc temperature from density, velocity, total energy per volume
   fn calc_temp(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64;
+  /// calc specific heat at constant pressure
+  /// in the unit consistent with calc_temp
+  fn calc_cp(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64;
+  /// calc specific heat at constant volume
+  /// in the unit consistent with calc_temp
+  fn calc_cv(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64;
   /// calc sound-speed from density, velocity, total energy per volume
   fn calc_cs(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64;
   /// calc total energy per volume
   /// from density, velocity, total specific enthalpy
+  #[allow(dead_code)]
   fn calc_e(&self, rho: &f64, u: &f64, v: &f64, h: &f64) -> f64;
   /// calc density and total energy per volume
   /// from pressure, temperature, velocity
+  #[allow(dead_code)]
   fn calc_rho_e(&self, p: &f64, temp: &f64, u: &f64, v: &f64,
     rho: &mut f64, e: &mut f64);
   /// calc total energy per volume from density, velocity, pressure
   fn calc_e_wp(&self, rho: &f64, u: &f64, v: &f64, p: &f64) -> f64;
+  /// calc derivatives of pressure from density, velocity, total energy per volume
+  /// returns (dp/drho at constant internal energy per volume,
+  /// dp/d(rho e_int) at constant density)
+  fn calc_dp(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> (f64, f64);
   /// eigen values/vectors of flux Jacobian
+  /// with the pressure derivatives given by calc_dp
+  #[inline]
+  #[allow(clippy::too_many_arguments)]
   fn calc_eigen(&self, rho: &f64, u: &f64, v: &f64, e: &f64,
-    ix: &f64, iy: &f64, dia_lam: &mut [f64; NF], 
-    mat_r: &mut [[f64; NF]; NF], mat_rinv: &mut [[f64; NF]; NF]);
+    ix: &f64, iy: &f64, dia_lam: &mut [f64; NE],
+    mat_r: &mut [[f64; NE]; NE], mat_rinv: &mut [[f64; NE]; NE]) {
+    let cs = self.calc_cs(rho, u, v, e);
+    let p = self.calc_p(rho, u, v, e);
+    let (dpdr, dpde) = self.calc_dp(rho, u, v, e);
+    calc_eigen_general(u, v, &cs, &((e + p) / rho), &(dpdr + 0.5 * dpde * (u * u + v * v)), &dpde,
+      ix, iy, dia_lam, mat_r, mat_rinv);
+  }
 }
 
+
+
+/// eigen values/vectors of flux Jacobian for general eos
+/// from sound-speed cs, total specific enthalpy h, and derivatives of pressure
+/// in terms of conservative variables, chi = dp/drho at constant momentum and total energy,
+/// and gr = dp/de at constant density and momentum (Gruneisen coefficient),
+/// which are 0.5 * (u^2 + v^2) * (gamma - 1) and gamma - 1 for ideal gas
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub fn calc_eigen_general(u: &f64, v: &f64, cs: &f64, h: &f64, chi: &f64, gr: &f64,
+  ix: &f64, iy: &f64, dia_lam: &mut [f64; NE],
+  mat_r: &mut [[f64; NE]; NE], mat_rinv: &mut [[f64; NE]; NE]) {
+    // preperation
+    let sqr = (ix * ix + iy * iy).sqrt();
+    let ixb = ix / sqr;
+    let iyb = iy / sqr;
+    let bigu = ix * u + iy * v;
+    let bigub = bigu / sqr;
+    let b1 = chi / cs / cs;
+    let b2 = gr / cs / cs;
+    // diagonal matrix of eigen values
+    dia_lam[0] = bigu - cs * sqr;
+    dia_lam[1] = bigu;
+    dia_lam[2] = bigu + cs * sqr;
+    dia_lam[3] = bigu;
+    // right eigen matrix
+    mat_r[0][0] = 1.0;
+    mat_r[0][1] = 1.0;
+    mat_r[0][2] = 1.0;
+    mat_r[0][3] = 0.0;
+    mat_r[1][0] = u - ixb * cs;
+    mat_r[1][1] = *u;
+    mat_r[1][2] = u + ixb * cs;
+    mat_r[1][3] = -iyb;
+    mat_r[2][0] = v - iyb * cs;
+    mat_r[2][1] = *v;
+    mat_r[2][2] = v + iyb * cs;
+    mat_r[2][3] = ixb;
+    mat_r[3][0] = h - cs * bigub;
+    mat_r[3][1] = h - cs * cs / gr;
+    mat_r[3][2] = h + cs * bigub;
+    mat_r[3][3] = -(iyb * u - ixb * v);
+    // inverse of righr eigen matrix
+    mat_rinv[0][0] = 0.5 * (b1 + bigub / cs);
+    mat_rinv[0][1] = -0.5 * (ixb / cs + b2 * u);
+    mat_rinv[0][2] = -0.5 * (iyb / cs + b2 * v);
+    mat_rinv[0][3] = 0.5 * b2;
+    mat_rinv[1][0] = 1.0 - b1;
+    mat_rinv[1][1] = b2 * u;
+    mat_rinv[1][2] = b2 * v;
+    mat_rinv[1][3] = -b2;
+    mat_rinv[2][0] = 0.5 * (b1 - bigub / cs);
+    mat_rinv[2][1] = 0.5 * (ixb / cs - b2 * u);
+    mat_rinv[2][2] = 0.5 * (iyb / cs - b2 * v);
+    mat_rinv[2][3] = 0.5 * b2;
+    mat_rinv[3][0] = iyb * u - ixb * v;
+    mat_rinv[3][1] = -iyb;
+    mat_rinv[3][2] = ixb;
+    mat_rinv[3][3] = 0.0;
+}
+
+
+
 impl EoS for super::IdealEoS {
+  /// not mixture
+  #[inline]
+  fn is_mixture(&self) -> bool {
+    false
+  }
+  /// identical to self
+  #[inline]
+  fn frozen(&self, _phi: &[f64; NS]) -> Self {
+    super::IdealEoS::new(&self.gamma, &self.cv)
+  }
+  /// description of eos for settings file
+  fn describe(&self) -> String {
+    format!("ideal gas eos.\n    gamma = {}\n    cv = {}\n    R = {}", self.gamma, self.cv, (self.gamma - 1.0) * self.cv)
+  }
   /// calc pressure from density, velocity, total energy per volume
   #[inline]
   fn calc_p(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
@@ -31,7 +146,17 @@ impl EoS for super::IdealEoS {
   /// calc temperature from density, velocity, total energy per volume
   #[inline]
   fn calc_temp(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
-    e / rho - 0.5 * (u * u + v * v)
+    (e / rho - 0.5 * (u * u + v * v)) / self.cv
+  }
+  /// calc specific heat at constant pressure
+  #[inline]
+  fn calc_cp(&self, _rho: &f64, _u: &f64, _v: &f64, _e: &f64) -> f64 {
+    self.gamma * self.cv
+  }
+  /// calc specific heat at constant volume
+  #[inline]
+  fn calc_cv(&self, _rho: &f64, _u: &f64, _v: &f64, _e: &f64) -> f64 {
+    self.cv
   }
   /// calc sound-speed from density, velocity, total energy per volume
   #[inline]
@@ -49,68 +174,467 @@ impl EoS for super::IdealEoS {
   #[inline]
   fn calc_rho_e(&self, p: &f64, temp: &f64, u: &f64, v: &f64,
     rho: &mut f64, e: &mut f64) {
-    *rho = p / temp / (self.gamma - 1.0);
-    *e = *rho * (temp + 0.5 * (u * u + v * v));
+    *rho = p / (self.cv * temp) / (self.gamma - 1.0);
+    *e = *rho * (self.cv * temp + 0.5 * (u * u + v * v));
   }
   /// calc total energy per volume from density, velocity, pressure
   #[inline]
   fn calc_e_wp(&self, rho: &f64, u: &f64, v: &f64, p: &f64) -> f64 {
     p / (self.gamma - 1.0) + 0.5 * rho * (u * u + v * v)
   }
-  /// eigen values/vectors of flux Jacobian
+  /// calc derivatives of pressure
   #[inline]
-  fn calc_eigen(&self, rho: &f64, u: &f64, v: &f64, e: &f64,
-    ix: &f64, iy: &f64, dia_lam: &mut [f64; NF], 
-    mat_r: &mut [[f64; NF]; NF], mat_rinv: &mut [[f64; NF]; NF]) {
-      // preperation
-      let cs = self.calc_cs(rho, u, v, e);
-      let p = self.calc_p(rho, u, v, e);
-      let h = (e + p) / rho;
-      let sqr = (ix * ix + iy * iy).sqrt();
-      let ixb = ix / sqr;
-      let iyb = iy / sqr;
-      let bigu = ix * u + iy * v;
-      let bigub = bigu / sqr;
-      let b1 = 0.5 * (u * u + v * v) * (self.gamma - 1.0) / cs / cs;
-      let b2 = (self.gamma - 1.0) / cs / cs;
-      // diagonal matrix of eigen values
-      dia_lam[0] = bigu - cs * sqr;
-      dia_lam[1] = bigu;
-      dia_lam[2] = bigu + cs * sqr;
-      dia_lam[3] = bigu;
-      // right eigen matrix
-      mat_r[0][0] = 1.0;
-      mat_r[0][1] = 1.0;
-      mat_r[0][2] = 1.0;
-      mat_r[0][3] = 0.0;
-      mat_r[1][0] = u - ixb * cs;
-      mat_r[1][1] = *u;
-      mat_r[1][2] = u + ixb * cs;
-      mat_r[1][3] = -iyb;
-      mat_r[2][0] = v - iyb * cs;
-      mat_r[2][1] = *v;
-      mat_r[2][2] = v + iyb * cs;
-      mat_r[2][3] = ixb;
-      mat_r[3][0] = h - cs * bigub;
-      mat_r[3][1] = 0.5 * (u * u + v * v);
-      mat_r[3][2] = h + cs * bigub;
-      mat_r[3][3] = -(iyb * u - ixb * v);
-      // inverse of righr eigen matrix
-      mat_rinv[0][0] = 0.5 * (b1 + bigub / cs);
-      mat_rinv[0][1] = -0.5 * (ixb / cs + b2 * u);
-      mat_rinv[0][2] = -0.5 * (iyb / cs + b2 * v);
-      mat_rinv[0][3] = 0.5 * b2;
-      mat_rinv[1][0] = 1.0 - b1;
-      mat_rinv[1][1] = b2 * u;
-      mat_rinv[1][2] = b2 * v;
-      mat_rinv[1][3] = -b2;
-      mat_rinv[2][0] = 0.5 * (b1 - bigub / cs);
-      mat_rinv[2][1] = 0.5 * (ixb / cs - b2 * u);
-      mat_rinv[2][2] = 0.5 * (iyb / cs - b2 * v);
-      mat_rinv[2][3] = 0.5 * b2;
-      mat_rinv[3][0] = iyb * u - ixb * v;
-      mat_rinv[3][1] = -iyb;
-      mat_rinv[3][2] = ixb;
-      mat_rinv[3][3] = 0.0;
+  fn calc_dp(&self, _rho: &f64, _u: &f64, _v: &f64, _e: &f64) -> (f64, f64) {
+    (0.0, self.gamma - 1.0)
+  }
+}
+
+
+
+impl EoS for super::StiffenedGasEoS {
+  /// not mixture
+  #[inline]
+  fn is_mixture(&self) -> bool {
+    false
+  }
+  /// identical to self
+  #[inline]
+  fn frozen(&self, _phi: &[f64; NS]) -> Self {
+    super::StiffenedGasEoS::new(&self.gamma, &self.cv, &self.p_inf)
+  }
+  /// description of eos for settings file
+  fn describe(&self) -> String {
+    format!("stiffened gas eos.\n    gamma = {}\n    cv = {}\n    p_inf = {}", self.gamma, self.cv, self.p_inf)
+  }
+  /// calc pressure from density, velocity, total energy per volume
+  #[inline]
+  fn calc_p(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    (self.gamma - 1.0) * (e - 0.5 * rho * (u * u + v * v)) - self.gamma * self.p_inf
+  }
+  /// calc temperature from density, velocity, total energy per volume
+  /// (p + p_inf) / ((gamma - 1) cv rho)
+  #[inline]
+  fn calc_temp(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    ((e - self.p_inf) / rho - 0.5 * (u * u + v * v)) / self.cv
+  }
+  /// calc specific heat at constant pressure
+  #[inline]
+  fn calc_cp(&self, _rho: &f64, _u: &f64, _v: &f64, _e: &f64) -> f64 {
+    self.gamma * self.cv
+  }
+  /// calc specific heat at constant volume
+  #[inline]
+  fn calc_cv(&self, _rho: &f64, _u: &f64, _v: &f64, _e: &f64) -> f64 {
+    self.cv
+  }
+  /// calc sound-speed from density, velocity, total energy per volume
+  #[inline]
+  fn calc_cs(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    (self.gamma * (self.calc_p(rho, u, v, e) + self.p_inf) / rho).sqrt()
+  }
+  /// calc total energy per volume
+  /// from density, velocity, total specific enthalpy
+  #[inline]
+  fn calc_e(&self, rho: &f64, u: &f64, v: &f64, h: &f64) -> f64 {
+    rho * (h + (self.gamma - 1.0) * 0.5 * (u * u + v * v)) / self.gamma + self.p_inf
+  }
+  /// calc density and total energy per volume
+  /// from pressure, temperature, velocity
+  #[inline]
+  fn calc_rho_e(&self, p: &f64, temp: &f64, u: &f64, v: &f64,
+    rho: &mut f64, e: &mut f64) {
+    *rho = (p + self.p_inf) / ((self.gamma - 1.0) * self.cv * temp);
+    *e = *rho * (self.cv * temp + 0.5 * (u * u + v * v)) + self.p_inf;
+  }
+  /// calc total energy per volume from density, velocity, pressure
+  #[inline]
+  fn calc_e_wp(&self, rho: &f64, u: &f64, v: &f64, p: &f64) -> f64 {
+    (p + self.gamma * self.p_inf) / (self.gamma - 1.0) + 0.5 * rho * (u * u + v * v)
+  }
+  /// calc derivatives of pressure
+  #[inline]
+  fn calc_dp(&self, _rho: &f64, _u: &f64, _v: &f64, _e: &f64) -> (f64, f64) {
+    (0.0, self.gamma - 1.0)
+  }
+}
+
+
+
+impl<const NSP: usize> EoS for super::MixtureEoS<NSP> {
+  /// mixture if more than one species
+  #[inline]
+  fn is_mixture(&self) -> bool {
+    NSP > 1
+  }
+  /// mixture of the composition given by phi,
+  /// where phi[0..NSP-1] are mass fractions of species 1, ..., NSP-1 and species 0 fills the rest
+  /// mass fractions are clipped into [0, 1] and normalized
+  /// cv is mass-weighted, and gamma = cp / cv with mass-weighted cp
+  #[inline]
+  fn frozen(&self, phi: &[f64; NS]) -> Self {
+    let mut y = [0.0; NSP];
+    for (y_k, phi_k) in y[1..].iter_mut().zip(phi) {
+      *y_k = phi_k.clamp(0.0, 1.0);
+    }
+    y[0] = (1.0 - y[1..].iter().sum::<f64>()).max(0.0);
+    let mut y_sum = 0.0;
+    let mut cv = 0.0;
+    let mut cp = 0.0;
+    for (k, y_k) in y.iter().enumerate() {
+      y_sum += y_k;
+      cv += y_k * self.cv_sp[k];
+      cp += y_k * self.gamma_sp[k] * self.cv_sp[k];
+    }
+    super::MixtureEoS {
+      gamma_sp: self.gamma_sp,
+      cv_sp: self.cv_sp,
+      ideal: super::IdealEoS::new(&(cp / cv), &(cv / y_sum)),
+    }
+  }
+  /// description of eos for settings file
+  fn describe(&self) -> String {
+    format!("mixture of {} ideal gases eos.\n    gamma of species = {:?}\n    cv of species = {:?}",
+      NSP, self.gamma_sp, self.cv_sp)
+  }
+  /// calc pressure from density, velocity, total energy per volume
+  #[inline]
+  fn calc_p(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    self.ideal.calc_p(rho, u, v, e)
+  }
+  /// calc temperature from density, velocity, total energy per volume
+  #[inline]
+  fn calc_temp(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    self.ideal.calc_temp(rho, u, v, e)
+  }
+  /// calc specific heat at constant pressure
+  #[inline]
+  fn calc_cp(&self, _rho: &f64, _u: &f64, _v: &f64, _e: &f64) -> f64 {
+    self.ideal.gamma * self.ideal.cv
+  }
+  /// calc specific heat at constant volume
+  #[inline]
+  fn calc_cv(&self, _rho: &f64, _u: &f64, _v: &f64, _e: &f64) -> f64 {
+    self.ideal.cv
+  }
+  /// calc sound-speed from density, velocity, total energy per volume
+  #[inline]
+  fn calc_cs(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    self.ideal.calc_cs(rho, u, v, e)
+  }
+  /// calc total energy per volume
+  /// from density, velocity, total specific enthalpy
+  #[inline]
+  fn calc_e(&self, rho: &f64, u: &f64, v: &f64, h: &f64) -> f64 {
+    self.ideal.calc_e(rho, u, v, h)
+  }
+  /// calc density and total energy per volume
+  /// from pressure, temperature, velocity
+  #[inline]
+  fn calc_rho_e(&self, p: &f64, temp: &f64, u: &f64, v: &f64,
+    rho: &mut f64, e: &mut f64) {
+    self.ideal.calc_rho_e(p, temp, u, v, rho, e);
+  }
+  /// calc total energy per volume from density, velocity, pressure
+  #[inline]
+  fn calc_e_wp(&self, rho: &f64, u: &f64, v: &f64, p: &f64) -> f64 {
+    self.ideal.calc_e_wp(rho, u, v, p)
+  }
+  /// calc derivatives of pressure
+  /// with the composition frozen
+  #[inline]
+  fn calc_dp(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> (f64, f64) {
+    self.ideal.calc_dp(rho, u, v, e)
+  }
+}
+
+
+
+/// solve f(x) = 0 by Newton's method from x0
+/// where fdf returns f and df/dx, keeping x above lower
+#[inline]
+fn solve_newton(fdf: impl Fn(f64) -> (f64, f64), x0: f64, lower: f64) -> f64 {
+  let mut x = x0.max(lower);
+  for _ in 0..100 {
+    let (f, df) = fdf(x);
+    let mut x_new = x - f / df;
+    if x_new <= lower {
+      x_new = 0.5 * (x + lower);
+    }
+    let dx = x_new - x;
+    x = x_new;
+    if dx.abs() <= 1.0e-13 * x.abs() {
+      break;
+    }
+  }
+  x
+}
+
+
+
+#[allow(dead_code)]
+impl super::CubicEoS {
+  /// gas constant, a, b, d1 and d2 of the cubic eos
+  #[inline]
+  fn coefficients(&self) -> (f64, f64, f64, f64, f64) {
+    let r = (self.gamma - 1.0) * self.cv;
+    match self.model {
+      "van_der_Waals" => (r, 27.0 / 64.0 * r * r * self.t_c * self.t_c / self.p_c,
+        0.125 * r * self.t_c / self.p_c, 0.0, 0.0),
+      "Peng_Robinson" => (r, 0.45724 * r * r * self.t_c * self.t_c / self.p_c,
+        0.07780 * r * self.t_c / self.p_c, 1.0 + 2.0_f64.sqrt(), 1.0 - 2.0_f64.sqrt()),
+      _ => panic!("Cubic eos not specified."),
     }
-}
\ No newline at end of file
+  }
+  /// temperature dependence of attraction, alpha and its first and second derivatives
+  #[inline]
+  fn calc_alpha(&self, temp: &f64) -> (f64, f64, f64) {
+    match self.model {
+      "Peng_Robinson" => {
+        let k = 0.37464 + 1.54226 * self.omega - 0.26992 * self.omega * self.omega;
+        let sq = (temp * self.t_c).sqrt();
+        let g = 1.0 + k * (1.0 - (temp / self.t_c).sqrt());
+        let dg = -0.5 * k / sq;
+        let ddg = 0.25 * k / temp / sq;
+        (g * g, 2.0 * g * dg, 2.0 * (dg * dg + g * ddg))
+      },
+      _ => (1.0, 0.0, 0.0),
+    }
+  }
+  /// pressure and its derivatives by temperature and specific volume vol
+  #[inline]
+  fn calc_p_tv(&self, temp: &f64, vol: &f64) -> (f64, f64, f64) {
+    let (r, a, b, d1, d2) = self.coefficients();
+    let (alpha, dalpha, _) = self.calc_alpha(temp);
+    let den = (vol + d1 * b) * (vol + d2 * b);
+    let p = r * temp / (vol - b) - a * alpha / den;
+    let p_t = r / (vol - b) - a * dalpha / den;
+    let p_v = -r * temp / (vol - b) / (vol - b) + a * alpha * (2.0 * vol + (d1 + d2) * b) / den / den;
+    (p, p_t, p_v)
+  }
+  /// internal energy per mass and its derivatives by temperature and specific volume vol
+  /// departure from ideal gas is (a alpha - T a dalpha) * f(vol), df/dvol = 1 / den
+  #[inline]
+  fn calc_e_tv(&self, temp: &f64, vol: &f64) -> (f64, f64, f64) {
+    let (_, a, b, d1, d2) = self.coefficients();
+    let (alpha, dalpha, ddalpha) = self.calc_alpha(temp);
+    let f = if d1 == d2 {
+      -1.0 / vol
+    } else {
+      ((vol + d2 * b) / (vol + d1 * b)).ln() / ((d1 - d2) * b)
+    };
+    let den = (vol + d1 * b) * (vol + d2 * b);
+    let e = self.cv * temp + a * (alpha - temp * dalpha) * f;
+    let e_t = self.cv - a * temp * ddalpha * f;
+    let e_v = a * (alpha - temp * dalpha) / den;
+    (e, e_t, e_v)
+  }
+  /// temperature from density and internal energy per mass
+  #[inline]
+  fn calc_temp_re(&self, rho: &f64, e_int: &f64) -> f64 {
+    let vol = 1.0 / rho;
+    let (_, a, _, _, _) = self.coefficients();
+    solve_newton(|t| {
+      let (e, e_t, _) = self.calc_e_tv(&t, &vol);
+      (e - e_int, e_t)
+    }, (e_int + a * rho) / self.cv, 1.0e-6 * self.t_c)
+  }
+}
+
+
+
+impl EoS for super::CubicEoS {
+  /// not mixture
+  #[inline]
+  fn is_mixture(&self) -> bool {
+    false
+  }
+  /// identical to self
+  #[inline]
+  fn frozen(&self, _phi: &[f64; NS]) -> Self {
+    super::CubicEoS::new(self.model, &self.gamma, &self.cv, &self.t_c, &self.p_c, &self.omega)
+  }
+  /// description of eos for settings file
+  fn describe(&self) -> String {
+    format!("{} eos.\n    gamma = {}\n    cv = {}\n    T_c = {}\n    p_c = {}\n    omega = {}",
+      self.model, self.gamma, self.cv, self.t_c, self.p_c, self.omega)
+  }
+  /// calc pressure from density, velocity, total energy per volume
+  #[inline]
+  fn calc_p(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    let temp = self.calc_temp(rho, u, v, e);
+    self.calc_p_tv(&temp, &(1.0 / rho)).0
+  }
+  /// calc temperature from density, velocity, total energy per volume
+  /// by Newton's method, exact in one step for van der Waals
+  #[inline]
+  fn calc_temp(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    self.calc_temp_re(rho, &(e / rho - 0.5 * (u * u + v * v)))
+  }
+  /// calc specific heat at constant pressure
+  /// cp = cv - T (dp/dT)^2 / (dp/dvol)
+  #[inline]
+  fn calc_cp(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    let temp = self.calc_temp(rho, u, v, e);
+    let (_, p_t, p_v) = self.calc_p_tv(&temp, &(1.0 / rho));
+    let (_, e_t, _) = self.calc_e_tv(&temp, &(1.0 / rho));
+    e_t - temp * p_t * p_t / p_v
+  }
+  /// calc specific heat at constant volume
+  #[inline]
+  fn calc_cv(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    let temp = self.calc_temp(rho, u, v, e);
+    self.calc_e_tv(&temp, &(1.0 / rho)).1
+  }
+  /// calc sound-speed from density, velocity, total energy per volume
+  /// c^2 = dp/drho + dp/d(rho e_int) * h_int
+  #[inline]
+  fn calc_cs(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    let p = self.calc_p(rho, u, v, e);
+    let (dpdr, dpde) = self.calc_dp(rho, u, v, e);
+    (dpdr + dpde * (e + p - 0.5 * rho * (u * u + v * v)) / rho).sqrt()
+  }
+  /// calc total energy per volume
+  /// from density, velocity, total specific enthalpy
+  /// by Newton's method for temperature
+  #[inline]
+  fn calc_e(&self, rho: &f64, u: &f64, v: &f64, h: &f64) -> f64 {
+    let vol = 1.0 / rho;
+    let ke = 0.5 * (u * u + v * v);
+    let temp = solve_newton(|t| {
+      let (e, e_t, _) = self.calc_e_tv(&t, &vol);
+      let (p, p_t, _) = self.calc_p_tv(&t, &vol);
+      (e + p * vol - (h - ke), e_t + p_t * vol)
+    }, (h - ke) / (self.gamma * self.cv), 1.0e-6 * self.t_c);
+    rho * (self.calc_e_tv(&temp, &vol).0 + ke)
+  }
+  /// calc density and total energy per volume
+  /// from pressure, temperature, velocity
+  /// by Newton's method for specific volume from the ideal gas,
+  /// giving the gas-like root below the critical temperature
+  #[inline]
+  fn calc_rho_e(&self, p: &f64, temp: &f64, u: &f64, v: &f64,
+    rho: &mut f64, e: &mut f64) {
+    let (r, _, b, _, _) = self.coefficients();
+    let vol = solve_newton(|vol| {
+      let (p_v0, _, p_v) = self.calc_p_tv(temp, &vol);
+      (p_v0 - p, p_v)
+    }, r * temp / p + b, b);
+    *rho = 1.0 / vol;
+    *e = *rho * (self.calc_e_tv(temp, &vol).0 + 0.5 * (u * u + v * v));
+  }
+  /// calc total energy per volume from density, velocity, pressure
+  /// by Newton's method for temperature
+  #[inline]
+  fn calc_e_wp(&self, rho: &f64, u: &f64, v: &f64, p: &f64) -> f64 {
+    let (r, a, b, d1, d2) = self.coefficients();
+    let vol = 1.0 / rho;
+    let temp = solve_newton(|t| {
+      let (p_t0, p_t, _) = self.calc_p_tv(&t, &vol);
+      (p_t0 - p, p_t)
+    }, (p + a / ((vol + d1 * b) * (vol + d2 * b))) * (vol - b) / r, 1.0e-6 * self.t_c);
+    rho * (self.calc_e_tv(&temp, &vol).0 + 0.5 * (u * u + v * v))
+  }
+  /// calc derivatives of pressure
+  /// from the derivatives by temperature and specific volume
+  #[inline]
+  fn calc_dp(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> (f64, f64) {
+    let vol = 1.0 / rho;
+    let e_int = e / rho - 0.5 * (u * u + v * v);
+    let temp = self.calc_temp_re(rho, &e_int);
+    let (_, p_t, p_v) = self.calc_p_tv(&temp, &vol);
+    let (_, e_t, e_v) = self.calc_e_tv(&temp, &vol);
+    let dpde = p_t / (rho * e_t);
+    let dpdr = -vol * vol * p_v - dpde * (e_int - vol * e_v);
+    (dpdr, dpde)
+  }
+}
+
+
+
+#[cfg(test)]
+/// check that R diag(lam) R^-1 dq is the directional derivative of the flux
+fn check_eigen<T: EoS>(eos: &T, rho: &f64, u: &f64, v: &f64, e: &f64) {
+  let (ix, iy) = (0.6, 0.8);
+  let mut dia_lam = [0.0; NE];
+  let mut mat_r = [[0.0; NE]; NE];
+  let mut mat_rinv = [[0.0; NE]; NE];
+  eos.calc_eigen(rho, u, v, e, &ix, &iy, &mut dia_lam, &mut mat_r, &mut mat_rinv);
+  let flux = |q: &[f64; NE]| {
+    let (rho, u, v) = (q[0], q[1] / q[0], q[2] / q[0]);
+    let p = eos.calc_p(&rho, &u, &v, &q[3]);
+    let bigu = ix * u + iy * v;
+    [rho * bigu, q[1] * bigu + ix * p, q[2] * bigu + iy * p, (q[3] + p) * bigu]
+  };
+  let q = [*rho, rho * u, rho * v, *e];
+  let dq = [1.0e-3 * rho, -2.0e-3 * rho, 0.5e-3 * rho, 1.0e-3 * e];
+  let eps = 1.0e-6;
+  let q_p: [f64; NE] = std::array::from_fn(|k| q[k] + eps * dq[k]);
+  let q_m: [f64; NE] = std::array::from_fn(|k| q[k] - eps * dq[k]);
+  let (f_p, f_m) = (flux(&q_p), flux(&q_m));
+  for k in 0..NE {
+    let mut adq = 0.0;
+    for l in 0..NE {
+      for m in 0..NE {
+        adq += mat_r[k][l] * dia_lam[l] * mat_rinv[l][m] * dq[m];
+      }
+    }
+    assert!((adq - 0.5 * (f_p[k] - f_m[k]) / eps).abs() < 1.0e-8 * (1.0 + e.abs()));
+  }
+}
+
+
+
+#[test]
+fn test_ideal_gas() {
+  // air in SI units, p = rho R T with R = (gamma - 1) cv
+  let eos = super::IdealEoS::new(&1.4, &717.5);
+  let (p, temp, u, v) = (101325.0, 300.0, 30.0, -20.0);
+  let (mut rho, mut e) = (0.0, 0.0);
+  eos.calc_rho_e(&p, &temp, &u, &v, &mut rho, &mut e);
+  assert!((rho - p / (0.4 * 717.5 * temp)).abs() < 1.0e-12 * rho);
+  assert!((eos.calc_p(&rho, &u, &v, &e) - p).abs() < 1.0e-10 * p);
+  assert!((eos.calc_temp(&rho, &u, &v, &e) - temp).abs() < 1.0e-10 * temp);
+  assert!((eos.calc_cp(&rho, &u, &v, &e) - eos.calc_cv(&rho, &u, &v, &e) - 0.4 * 717.5).abs() < 1.0e-10);
+  check_eigen(&eos, &rho, &u, &v, &e);
+}
+
+
+
+#[test]
+fn test_stiffened_gas() {
+  // water normalized by 1000 kg/m^3 and 1 GPa
+  let eos = super::StiffenedGasEoS::new(&4.4, &2.5, &0.6);
+  let (rho, u, v, p) = (1.0, 0.3, -0.2, 1.0e-4);
+  let e = eos.calc_e_wp(&rho, &u, &v, &p);
+  assert!((eos.calc_p(&rho, &u, &v, &e) - p).abs() < 1.0e-12);
+  let h = (e + p) / rho;
+  assert!((eos.calc_e(&rho, &u, &v, &h) - e).abs() < 1.0e-12);
+  let (mut rho_t, mut e_t) = (0.0, 0.0);
+  eos.calc_rho_e(&p, &eos.calc_temp(&rho, &u, &v, &e), &u, &v, &mut rho_t, &mut e_t);
+  assert!((rho_t - rho).abs() < 1.0e-12 && (e_t - e).abs() < 1.0e-12);
+  assert!((eos.calc_cp(&rho, &u, &v, &e) - eos.calc_cv(&rho, &u, &v, &e) - 3.4 * 2.5).abs() < 1.0e-12);
+  check_eigen(&eos, &rho, &u, &v, &e);
+}
+
+
+
+#[test]
+fn test_cubic_eos() {
+  // supercritical CO2 normalized by the critical point
+  for (model, cv) in [("van_der_Waals", 1.0), ("Peng_Robinson", 2.5)] {
+    let eos = super::CubicEoS::new(model, &1.29, &cv, &1.0, &1.0, &0.225);
+    let (p, temp, u, v) = (1.2, 1.1, 0.3, -0.2);
+    let (mut rho, mut e) = (0.0, 0.0);
+    eos.calc_rho_e(&p, &temp, &u, &v, &mut rho, &mut e);
+    assert!((eos.calc_p(&rho, &u, &v, &e) - p).abs() < 1.0e-10);
+    assert!((eos.calc_temp(&rho, &u, &v, &e) - temp).abs() < 1.0e-10);
+    assert!((eos.calc_e_wp(&rho, &u, &v, &p) - e).abs() < 1.0e-10);
+    assert!((eos.calc_e(&rho, &u, &v, &((e + p) / rho)) - e).abs() < 1.0e-10);
+    // sound-speed from the isentropic derivative dp/drho = (dp/drho)_T cp / cv
+    let vol = 1.0 / rho;
+    let (_, _, p_v) = eos.calc_p_tv(&temp, &vol);
+    let cs2 = -vol * vol * p_v * eos.calc_cp(&rho, &u, &v, &e) / eos.calc_cv(&rho, &u, &v, &e);
+    assert!((eos.calc_cs(&rho, &u, &v, &e).powi(2) - cs2).abs() < 1.0e-10 * cs2);
+    check_eigen(&eos, &rho, &u, &v, &e);
+  }
+}
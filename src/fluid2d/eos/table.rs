@@ -0,0 +1,333 @@
+use std::fs::File;
+use std::io::{BufReader, BufRead};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use super::super::{EoSTable, TabulatedEoS, NS};
+use super::{calc_eigen_general, solve_newton, EoS, NE};
+
+
+
+/// index k of the cell [axis[k], axis[k+1]] nearest to x, local coordinate of x in it,
+/// and -1/1 if x is below/above the range of axis, 0 otherwise
+#[inline]
+fn locate(axis: &[f64], x: &f64) -> (usize, f64, i8) {
+  let n = axis.len();
+  let k = axis.partition_point(|a| a <= x).clamp(1, n - 1) - 1;
+  let t = (x - axis[k]) / (axis[k+1] - axis[k]);
+  let out = if *x < axis[0] {
+    -1
+  } else if *x > axis[n-1] {
+    1
+  } else {
+    0
+  };
+  (k, t, out)
+}
+
+/// cubic Hermite interpolation of f in the cell [axis[k], axis[k+1]] at local coordinate t
+/// with slopes by central difference, one-sided at the ends of axis
+/// returns value and derivative
+#[inline]
+fn hermite(axis: &[f64], f: impl Fn(usize) -> f64, k: usize, t: &f64) -> (f64, f64) {
+  let n = axis.len();
+  let slope = |m: usize| {
+    let (a, b) = (m.saturating_sub(1), (m + 1).min(n - 1));
+    (f(b) - f(a)) / (axis[b] - axis[a])
+  };
+  let h = axis[k+1] - axis[k];
+  let (f0, f1, m0, m1) = (f(k), f(k+1), slope(k) * h, slope(k+1) * h);
+  let t2 = t * t;
+  let t3 = t2 * t;
+  let val = (2.0 * t3 - 3.0 * t2 + 1.0) * f0 + (t3 - 2.0 * t2 + t) * m0
+    + (-2.0 * t3 + 3.0 * t2) * f1 + (t3 - t2) * m1;
+  let der = ((6.0 * t2 - 6.0 * t) * f0 + (3.0 * t2 - 4.0 * t + 1.0) * m0
+    + (-6.0 * t2 + 6.0 * t) * f1 + (3.0 * t2 - 2.0 * t) * m1) / h;
+  (val, der)
+}
+
+
+
+impl TabulatedEoS {
+  /// load table from file
+  /// the first line is the numbers of grid points of density and internal energy,
+  /// followed by density, internal energy per mass,
+  /// and pressure, sound-speed, temperature at [rho][e_int], one value per line
+  /// called in the main program before initialize if TabulatedEoS replaces the eos there
+  #[allow(dead_code)]
+  pub fn input(&mut self, f_name: &str) {
+    let f = File::open(f_name).unwrap();
+    let buf = BufReader::new(f);
+    let mut lines = buf.lines();
+    let head = lines.next().expect("Eos table is empty.").unwrap();
+    let n: Vec<usize> = head.split_whitespace().map(|w| w.parse::<usize>().unwrap()).collect();
+    let (n_rho, n_e) = (n[0], n[1]);
+    if n_rho < 2 || n_e < 2 {
+      panic!("Eos table should have at least 2 grid points in each direction.");
+    }
+    let val: Vec<f64> = lines.map(|l| l.unwrap().trim().parse::<f64>().unwrap()).collect();
+    if val.len() != n_rho + n_e + 3 * n_rho * n_e {
+      panic!("Data size of eos table may be wrong.");
+    }
+    let rho = val[..n_rho].to_vec();
+    let e_int = val[n_rho..n_rho+n_e].to_vec();
+    if rho.windows(2).any(|w| w[0] >= w[1]) || e_int.windows(2).any(|w| w[0] >= w[1]) {
+      panic!("Grid of eos table should be increasing.");
+    }
+    let block = |c: usize| -> Vec<Vec<f64>> {
+      let offset = n_rho + n_e + c * n_rho * n_e;
+      (0..n_rho).map(|i| val[offset+i*n_e..offset+(i+1)*n_e].to_vec()).collect()
+    };
+    // shared by the frozen copies
+    self.table = Some(Arc::new(EoSTable {
+      f_name: f_name.to_string(),
+      rho,
+      e_int,
+      p: block(0),
+      cs: block(1),
+      temp: block(2),
+      n_out: [AtomicUsize::new(0), AtomicUsize::new(0), AtomicUsize::new(0), AtomicUsize::new(0)],
+    }));
+  }
+
+  /// loaded table
+  #[inline]
+  fn table(&self) -> &EoSTable {
+    self.table.as_deref().expect("Eos table not loaded.")
+  }
+
+  /// value of table f and its derivatives by density and internal energy per mass
+  /// interpolated with the scheme selected by self.interpolation,
+  /// or extrapolated linearly from the edge cells out of range, which is only counted here and reported by diagnostics
+  #[inline]
+  fn interpolate(&self, f: &[Vec<f64>], rho: &f64, e_int: &f64) -> (f64, f64, f64) {
+    let table = self.table();
+    let (i, t, out_r) = locate(&table.rho, rho);
+    let (j, s, out_e) = locate(&table.e_int, e_int);
+    for (k, out) in [(0, -out_r), (1, out_r), (2, -out_e), (3, out_e)] {
+      if out == 1 {
+        table.n_out[k].fetch_add(1, Ordering::Relaxed);
+      }
+    }
+    let interpolation = if out_r != 0 || out_e != 0 {
+      "bilinear"
+    } else {
+      self.interpolation
+    };
+    match interpolation {
+      "bilinear" => {
+        let (f00, f01, f10, f11) = (f[i][j], f[i][j+1], f[i+1][j], f[i+1][j+1]);
+        let val = (1.0 - t) * ((1.0 - s) * f00 + s * f01) + t * ((1.0 - s) * f10 + s * f11);
+        let der_r = ((1.0 - s) * (f10 - f00) + s * (f11 - f01)) / (table.rho[i+1] - table.rho[i]);
+        let der_e = ((1.0 - t) * (f01 - f00) + t * (f11 - f10)) / (table.e_int[j+1] - table.e_int[j]);
+        (val, der_r, der_e)
+      },
+      "bicubic" => {
+        // tensor product of cubic Hermite interpolations, first in e_int and then in rho
+        let (val, der_r) = hermite(&table.rho, |ii| hermite(&table.e_int, |jj| f[ii][jj], j, &s).0, i, &t);
+        let (der_e, _) = hermite(&table.rho, |ii| hermite(&table.e_int, |jj| f[ii][jj], j, &s).1, i, &t);
+        (val, der_r, der_e)
+      },
+      _ => panic!("Interpolation of eos table not specified."),
+    }
+  }
+}
+
+
+
+impl EoS for TabulatedEoS {
+  /// not mixture
+  #[inline]
+  fn is_mixture(&self) -> bool {
+    false
+  }
+  /// identical to self, sharing the table
+  #[inline]
+  fn frozen(&self, _phi: &[f64; NS]) -> Self {
+    TabulatedEoS {
+      table: self.table.clone(),
+      interpolation: self.interpolation,
+    }
+  }
+  /// description of eos for settings file
+  fn describe(&self) -> String {
+    let table = self.table();
+    format!("tabulated eos.\n    table = {}\n    interpolation = {}\n    grid = {} x {}\n    rho = [{}, {}]\n    e_int = [{}, {}]",
+      table.f_name, self.interpolation, table.rho.len(), table.e_int.len(),
+      table.rho[0], table.rho[table.rho.len()-1], table.e_int[0], table.e_int[table.e_int.len()-1])
+  }
+  /// numbers of evaluations out of the range of the table
+  fn diagnostics(&self) -> String {
+    let table = self.table();
+    let n: Vec<usize> = table.n_out.iter().map(|n| n.load(Ordering::Relaxed)).collect();
+    if n.iter().all(|n| *n == 0) {
+      String::new()
+    } else {
+      format!("Warning: eos table {} was extrapolated.\n    evaluations with rho below/above the range = {}/{}\n    evaluations with e_int below/above the range = {}/{}",
+        table.f_name, n[0], n[1], n[2], n[3])
+    }
+  }
+  /// calc pressure from density, velocity, total energy per volume
+  #[inline]
+  fn calc_p(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    self.interpolate(&self.table().p, rho, &(e / rho - 0.5 * (u * u + v * v))).0
+  }
+  /// calc temperature from density, velocity, total energy per volume
+  #[inline]
+  fn calc_temp(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    self.interpolate(&self.table().temp, rho, &(e / rho - 0.5 * (u * u + v * v))).0
+  }
+  /// calc specific heat at constant pressure
+  /// from the derivatives of pressure and temperature along the isobar
+  #[inline]
+  fn calc_cp(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    let e_int = e / rho - 0.5 * (u * u + v * v);
+    let (p, p_r, p_e) = self.interpolate(&self.table().p, rho, &e_int);
+    let (_, t_r, t_e) = self.interpolate(&self.table().temp, rho, &e_int);
+    let h_r = p_r / rho - p / rho / rho;
+    let h_e = 1.0 + p_e / rho;
+    (h_e * p_r - h_r * p_e) / (t_e * p_r - t_r * p_e)
+  }
+  /// calc specific heat at constant volume
+  #[inline]
+  fn calc_cv(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    1.0 / self.interpolate(&self.table().temp, rho, &(e / rho - 0.5 * (u * u + v * v))).2
+  }
+  /// calc sound-speed from density, velocity, total energy per volume
+  /// interpolated from the table
+  #[inline]
+  fn calc_cs(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> f64 {
+    self.interpolate(&self.table().cs, rho, &(e / rho - 0.5 * (u * u + v * v))).0
+  }
+  /// calc total energy per volume
+  /// from density, velocity, total specific enthalpy
+  /// by Newton's method for internal energy
+  #[inline]
+  fn calc_e(&self, rho: &f64, u: &f64, v: &f64, h: &f64) -> f64 {
+    let table = self.table();
+    let ke = 0.5 * (u * u + v * v);
+    let e_int = solve_newton(|e_int| {
+      let (p, _, p_e) = self.interpolate(&table.p, rho, &e_int);
+      (e_int + p / rho - (h - ke), 1.0 + p_e / rho)
+    }, (h - ke).clamp(table.e_int[0], table.e_int[table.e_int.len()-1]), f64::MIN);
+    rho * (e_int + ke)
+  }
+  /// calc density and total energy per volume
+  /// from pressure, temperature, velocity
+  /// by Newton's method from the nearest grid point
+  #[inline]
+  fn calc_rho_e(&self, p: &f64, temp: &f64, u: &f64, v: &f64,
+    rho: &mut f64, e: &mut f64) {
+    let table = self.table();
+    let mut dist = f64::MAX;
+    for i in 0..table.rho.len() {
+      for j in 0..table.e_int.len() {
+        let d = ((table.p[i][j] - p) / p).abs() + ((table.temp[i][j] - temp) / temp).abs();
+        if d < dist {
+          dist = d;
+          *rho = table.rho[i];
+          *e = table.e_int[j];
+        }
+      }
+    }
+    for _ in 0..100 {
+      let (p_n, p_r, p_e) = self.interpolate(&table.p, rho, e);
+      let (t_n, t_r, t_e) = self.interpolate(&table.temp, rho, e);
+      let det = p_r * t_e - p_e * t_r;
+      let d_rho = ((p - p_n) * t_e - p_e * (temp - t_n)) / det;
+      let d_e = (p_r * (temp - t_n) - (p - p_n) * t_r) / det;
+      *rho += d_rho;
+      *e += d_e;
+      if d_rho.abs() <= 1.0e-13 * rho.abs() && d_e.abs() <= 1.0e-13 * e.abs() {
+        break;
+      }
+    }
+    *e = *rho * (*e + 0.5 * (u * u + v * v));
+  }
+  /// calc total energy per volume from density, velocity, pressure
+  /// by Newton's method for internal energy from the grid point bracketing the pressure
+  #[inline]
+  fn calc_e_wp(&self, rho: &f64, u: &f64, v: &f64, p: &f64) -> f64 {
+    let table = self.table();
+    let (i, t, _) = locate(&table.rho, rho);
+    let row = &table.p[if t < 0.5 { i } else { i + 1 }];
+    let j = row.partition_point(|p_j| p_j < p).min(row.len() - 1);
+    let e_int = solve_newton(|e_int| {
+      let (p_n, _, p_e) = self.interpolate(&table.p, rho, &e_int);
+      (p_n - p, p_e)
+    }, table.e_int[j], f64::MIN);
+    rho * (e_int + 0.5 * (u * u + v * v))
+  }
+  /// calc derivatives of pressure
+  /// from the derivatives of the interpolation
+  #[inline]
+  fn calc_dp(&self, rho: &f64, u: &f64, v: &f64, e: &f64) -> (f64, f64) {
+    let e_int = e / rho - 0.5 * (u * u + v * v);
+    let (_, p_r, p_e) = self.interpolate(&self.table().p, rho, &e_int);
+    (p_r - p_e * e_int / rho, p_e / rho)
+  }
+  /// eigen values/vectors of flux Jacobian
+  /// with sound-speed from calc_dp instead of the table for consistency
+  #[inline]
+  fn calc_eigen(&self, rho: &f64, u: &f64, v: &f64, e: &f64,
+    ix: &f64, iy: &f64, dia_lam: &mut [f64; NE],
+    mat_r: &mut [[f64; NE]; NE], mat_rinv: &mut [[f64; NE]; NE]) {
+    let p = self.calc_p(rho, u, v, e);
+    let (dpdr, dpde) = self.calc_dp(rho, u, v, e);
+    let h = (e + p) / rho;
+    let ke = 0.5 * (u * u + v * v);
+    let cs = (dpdr + dpde * (h - ke)).sqrt();
+    calc_eigen_general(u, v, &cs, &h, &(dpdr + dpde * ke), &dpde, ix, iy, dia_lam, mat_r, mat_rinv);
+  }
+}
+
+
+
+#[test]
+fn test_tabulated_eos() {
+  // table of supercritical CO2 by Peng-Robinson eos normalized by the critical point
+  let pr = super::super::CubicEoS::new("Peng_Robinson", &1.29, &1.0, &1.0, &1.0, &0.225);
+  let (n_rho, n_e) = (41, 41);
+  let rho: Vec<f64> = (0..n_rho).map(|i| 1.0 + 8.0 * i as f64 / (n_rho - 1) as f64).collect();
+  let e_int: Vec<f64> = (0..n_e).map(|j| 0.5 + 2.5 * j as f64 / (n_e - 1) as f64).collect();
+  let f_name = std::env::temp_dir().join(format!("fluid2d_test_eos_table_{}.dat", std::process::id()));
+  let mut text = format!("{} {}\n", n_rho, n_e);
+  for x in rho.iter().chain(e_int.iter()) {
+    text += &format!("{:.18e}\n", x);
+  }
+  for c in 0..3 {
+    for r in &rho {
+      for e in &e_int {
+        let val = match c {
+          0 => pr.calc_p(r, &0.0, &0.0, &(r * e)),
+          1 => pr.calc_cs(r, &0.0, &0.0, &(r * e)),
+          _ => pr.calc_temp(r, &0.0, &0.0, &(r * e)),
+        };
+        text += &format!("{:.18e}\n", val);
+      }
+    }
+  }
+  std::fs::write(&f_name, text).unwrap();
+  let mut err = [0.0; 2];
+  let (r, u, v, e_i) = (4.3, 0.3, -0.2, 1.37);
+  let e = r * (e_i + 0.5 * (u * u + v * v));
+  let p_ex = pr.calc_p(&r, &u, &v, &e);
+  for (n, interpolation) in ["bilinear", "bicubic"].iter().enumerate() {
+    let mut eos = TabulatedEoS::new(interpolation);
+    eos.input(f_name.to_str().unwrap());
+    err[n] = (eos.calc_p(&r, &u, &v, &e) - p_ex).abs() / p_ex;
+    // round trips
+    let p = eos.calc_p(&r, &u, &v, &e);
+    assert!((eos.calc_e_wp(&r, &u, &v, &p) - e).abs() < 1.0e-10);
+    assert!((eos.calc_e(&r, &u, &v, &((e + p) / r)) - e).abs() < 1.0e-10);
+    let (mut r_t, mut e_t) = (0.0, 0.0);
+    eos.calc_rho_e(&p, &eos.calc_temp(&r, &u, &v, &e), &u, &v, &mut r_t, &mut e_t);
+    assert!((r_t - r).abs() < 1.0e-10 && (e_t - e).abs() < 1.0e-10);
+    super::check_eigen(&eos, &r, &u, &v, &e);
+    // out-of-range evaluation is reported
+    assert!(eos.diagnostics().is_empty());
+    eos.calc_p(&10.0, &u, &v, &e);
+    assert!(!eos.diagnostics().is_empty());
+  }
+  assert!(err[1] < 1.0e-5 && err[1] < 0.1 * err[0]);
+  std::fs::remove_file(&f_name).unwrap();
+}
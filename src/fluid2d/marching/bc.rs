@@ -1,15 +1,21 @@
 use super::super::{eos::EoS, BasicVarHD};
 
+#[allow(clippy::upper_case_acronyms)]
 pub trait BCHD<const NI: usize, const NJ: usize, const NB: usize> {
   fn reflect_bc<T: EoS>(&self, bc_type: &str, eos: &T, basic: &mut BasicVarHD::<NI,NJ,NB>);
   fn bc_periodical_in_i(&self, basic: &mut BasicVarHD::<NI,NJ,NB>);
+  fn bc_axis_at_j0(&self, basic: &mut BasicVarHD::<NI,NJ,NB>);
 }
 
 impl<const NI: usize, const NJ:usize, const NB: usize> BCHD::<NI,NJ,NB>
 for super::super::Boundary::<NI,NJ,NB> {
-  fn reflect_bc<T: EoS>(&self, bc_type: &str, eos: &T, basic: &mut BasicVarHD::<NI,NJ,NB>) {
+  fn reflect_bc<T: EoS>(&self, bc_type: &str, _eos: &T, basic: &mut BasicVarHD::<NI,NJ,NB>) {
     match bc_type {
       "periodical_in_i" => self.bc_periodical_in_i(basic),
+      "periodical_in_i_with_axis" => {
+        self.bc_periodical_in_i(basic);
+        self.bc_axis_at_j0(basic);
+      },
       _ => panic!("BC not specified."),
     }
   }
@@ -24,6 +30,7 @@ for super::super::Boundary::<NI,NJ,NB> {
         basic.u[i][j] = basic.u[NI-2*NB+i][j];
         basic.v[i][j] = basic.v[NI-2*NB+i][j];
         basic.e[i][j] = basic.e[NI-2*NB+i][j];
+        basic.phi[i][j] = basic.phi[NI-2*NB+i][j];
       }
     }
     // for i=Ni
@@ -33,8 +40,24 @@ for super::super::Boundary::<NI,NJ,NB> {
         basic.u[NI-NB+i][j] = basic.u[NB+i][j];
         basic.v[NI-NB+i][j] = basic.v[NB+i][j];
         basic.e[NI-NB+i][j] = basic.e[NB+i][j];
+        basic.phi[NI-NB+i][j] = basic.phi[NB+i][j];
       }
     }
     // No updates for j-boundaries since Dirichlet
   } 
-}
\ No newline at end of file
+
+  fn bc_axis_at_j0(&self, basic: &mut BasicVarHD::<NI,NJ,NB>)
+  {
+    // axis of symmetry at j=0 for axisymmetric flow
+    // mirroring with radial velocity reversed
+    for i in 0..NI {
+      for j in 0..NB {
+        basic.rho[i][NB-1-j] = basic.rho[i][NB+j];
+        basic.u[i][NB-1-j] = basic.u[i][NB+j];
+        basic.v[i][NB-1-j] = -basic.v[i][NB+j];
+        basic.e[i][NB-1-j] = basic.e[i][NB+j];
+        basic.phi[i][NB-1-j] = basic.phi[i][NB+j];
+      }
+    }
+  }
+}
@@ -0,0 +1,87 @@
+use super::super::{eos::EoS, basic_var::BasicVar};
+
+
+
+impl<const NI: usize, const NJ: usize, const NB: usize, T: EoS> super::super::IdealGas::<NI,NJ,NB,T> {
+  /// first cell (i, j) in the computational domain with non-finite values or non-positive density or pressure
+  pub(super) fn find_bad_cell(&self) -> Option<(usize, usize)> {
+    for i in NB..(NI-NB) {
+      for j in NB..(NJ-NB) {
+        let p = self.eos.frozen(&self.basic.phi[i][j]).calc_p(&self.basic.rho[i][j], &self.basic.u[i][j], &self.basic.v[i][j], &self.basic.e[i][j]);
+        if !(self.basic.rho[i][j] > 0.0 && p > 0.0 && self.basic.u[i][j].is_finite() && self.basic.v[i][j].is_finite()
+          && self.basic.e[i][j].is_finite() && self.basic.phi[i][j].iter().all(|phi| phi.is_finite())) {
+          return Some((i, j));
+        }
+      }
+    }
+    None
+  }
+
+
+  /// state of the cell (i, j) in SI units
+  fn describe_cell(&self, i: &usize, j: &usize) -> String {
+    let (i, j) = (*i, *j);
+    let p = self.eos.frozen(&self.basic.phi[i][j]).calc_p(&self.basic.rho[i][j], &self.basic.u[i][j], &self.basic.v[i][j], &self.basic.e[i][j]);
+    format!("({:5}, {:5}): rho = {:.6e}, u = {:.6e}, v = {:.6e}, p = {:.6e}, phi = {:?}",
+      i, j, self.basic.rho[i][j] * self.reference.density, self.basic.u[i][j] * self.reference.velocity,
+      self.basic.v[i][j] * self.reference.velocity, p * self.reference.pressure(), self.basic.phi[i][j])
+  }
+
+
+  /// stop the calculation for blow-up detected at stage in the cell (i, j),
+  /// showing the state of the cell and its neighbours in SI units,
+  /// after writing the current basic variables into emergency.dat in dir_o
+  pub(super) fn abort_blow_up(&self, stage: &str, i: &usize, j: &usize) -> ! {
+    let f_name = self.dir_o.clone() + "emergency.dat";
+    self.basic.output_snapshot(&self.reference, &f_name);
+    let mut msg = format!("Blow-up is detected at {} in the cell (i, j) = ({}, {}), (x, y) = ({:.6e}, {:.6e}).\nstate of the cell and neighbours:",
+      stage, i, j, self.coord.x[*i][*j] * self.reference.length, self.coord.y[*i][*j] * self.reference.length);
+    for (i_n, j_n) in [(*i, *j), (i - 1, *j), (i + 1, *j), (*i, j - 1), (*i, j + 1)] {
+      msg += &format!("\n  {}", self.describe_cell(&i_n, &j_n));
+    }
+    msg += &format!("\nBasic variables are written into {}.", f_name);
+    panic!("{}", msg);
+  }
+}
+
+
+
+#[test]
+fn test_blow_up() {
+  const NI: usize = 40;
+  const NJ: usize = 12;
+  const NB: usize = 4;
+  let xy = |i: usize, j: usize| (0.1 * i as f64, 0.1 * j as f64);
+  let mut fluid = super::super::IdealGas::<NI,NJ,NB,_>::new_for_test(super::super::IdealEoS::new(&1.4, &1.0), xy, &false);
+  for i in 0..NI {
+    for j in 0..NJ {
+      fluid.basic.rho[i][j] = 1.0;
+      fluid.basic.e[i][j] = 2.5;
+    }
+  }
+  assert_eq!(fluid.find_bad_cell(), None);
+  // NaN of velocity, and negative pressure found first in the order of i and then j
+  fluid.basic.u[20][6] = f64::NAN;
+  assert_eq!(fluid.find_bad_cell(), Some((20, 6)));
+  fluid.basic.e[12][7] = -0.1;
+  assert_eq!(fluid.find_bad_cell(), Some((12, 7)));
+  // bad values in dummy cells are not checked
+  fluid.basic.rho[2][5] = f64::NAN;
+  assert_eq!(fluid.find_bad_cell(), Some((12, 7)));
+
+  let dir_o = std::env::temp_dir().join(format!("fluid2d_blow_up_{}", std::process::id()));
+  std::fs::create_dir_all(&dir_o).unwrap();
+  fluid.dir_o = dir_o.to_str().unwrap().to_string() + "/";
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| fluid.abort_blow_up("test", &12, &7)));
+  let msg = result.unwrap_err().downcast::<String>().unwrap();
+  assert!(msg.contains("(i, j) = (12, 7), (x, y) = (1.200000e0, 7.000000e-1)"), "{}", msg);
+  assert!(msg.contains("(   12,     7): rho = 1.000000e0, u = 0.000000e0, v = 0.000000e0, p = -4.000000e-2"), "{}", msg);
+  // the snapshot is readable as the initial condition
+  let mut basic = super::super::BasicVarHD::<NI,NJ,NB>::new();
+  let f_name = fluid.dir_o.clone() + "emergency.dat";
+  basic.input(&f_name);
+  std::fs::remove_dir_all(&dir_o).unwrap();
+  assert_eq!(basic.e[12][7], -0.1);
+  assert!(basic.u[20][6].is_nan() && basic.rho[2][5].is_nan());
+  assert_eq!((basic.rho[39][11], basic.e[0][0]), (1.0, 2.5));
+}
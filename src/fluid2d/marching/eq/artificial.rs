@@ -0,0 +1,252 @@
+use super::super::super::{eos::EoS, ArtificialDissipation, EulerEq, GenStructCoord, HybridScheme, NE, NF, NS};
+use super::{fnd, viscous::calc_v_r};
+
+
+
+impl ArtificialDissipation {
+  /// whether artificial dissipation is added
+  #[inline]
+  pub fn is_active(&self) -> bool {
+    self.model != "none"
+  }
+
+  /// whether JST dissipation and artificial bulk viscosity are added, respectively
+  #[inline]
+  fn switches(&self) -> (bool, bool) {
+    match self.model {
+      "JST" => (true, false),
+      "VNR" => (false, true),
+      "JST_VNR" => (true, true),
+      "none" => (false, false),
+      _ => panic!("Artificial dissipation not specified."),
+    }
+  }
+
+  /// description of artificial dissipation for settings file
+  pub fn describe(&self) -> String {
+    format!("artificial dissipation = {}
+    k2 = {}
+    k4 = {}
+    c_vnr = {}", self.model, self.k2, self.k4, self.c_vnr)
+  }
+
+  /// JST dissipation at the face between the cells 1 and 2 of the stencil of 4 cells,
+  /// from conservative variables per volume q, pressure p, and spectral radius lambda at the face
+  #[inline]
+  fn calc_jst(&self, q: &[[f64; NF]; 4], p: &[f64; 4], lambda: &f64, vec_d: &mut [f64; NF]) {
+    // pressure sensor of the cells 1 and 2
+    let nu = |pm: &f64, pc: &f64, pp: &f64| (pp - 2.0 * pc + pm).abs() / (pp + 2.0 * pc + pm);
+    let eps2 = self.k2 * nu(&p[0], &p[1], &p[2]).max(nu(&p[1], &p[2], &p[3]));
+    let eps4 = (self.k4 - eps2).max(0.0);
+    for k in 0..NF {
+      vec_d[k] = lambda * (eps2 * (q[2][k] - q[1][k]) - eps4 * fnd::third_diff(&q[0][k], &q[1][k], &q[2][k], &q[3][k]));
+    }
+  }
+
+  /// flux of artificial bulk viscosity of von Neumann-Richtmyer type at the face in the direction of (ixs, iys)
+  /// from density, velocity and its divergence at the face, and the Jacobian inverse s
+  #[inline]
+  #[allow(clippy::too_many_arguments)]
+  fn calc_vnr(&self, rho: &f64, u: &f64, v: &f64, div: &f64, ixs: &f64, iys: &f64, s: &f64, vec_d: &mut [f64; NF]) {
+    *vec_d = [0.0; NF];
+    if *div < 0.0 {
+      // square of grid spacing normal to the face
+      let dx2 = s * s / (ixs * ixs + iys * iys);
+      let beta = self.c_vnr * rho * dx2 * div.abs();
+      vec_d[1] = ixs * beta * div;
+      vec_d[2] = iys * beta * div;
+      vec_d[3] = (ixs * u + iys * v) * beta * div;
+    }
+  }
+}
+
+
+
+/// conservative variables per volume of the cell
+#[inline]
+fn calc_q(rho: &f64, u: &f64, v: &f64, e: &f64, phi: &[f64; NS]) -> [f64; NF] {
+  let mut vec_q = [0.0; NF];
+  vec_q[0] = *rho;
+  vec_q[1] = rho * u;
+  vec_q[2] = rho * v;
+  vec_q[3] = *e;
+  for (q, p) in vec_q[NE..].iter_mut().zip(phi) {
+    *q = rho * p;
+  }
+  vec_q
+}
+
+
+
+impl<const NI: usize, const NJ: usize, const NB: usize> EulerEq::<NI,NJ,NB> {
+  /// subtract artificial dissipation from numerical flux in arr_fi and arr_fj at the faces of the central flux of hybrid
+  /// spectral radius by the average of both sides, and divergence of velocity in the same way as viscous flux
+  /// pressure of the cells in arr_p, and for mixture, eos is frozen in each cell by phi_frozen and arr_fi_r and arr_fj_r are also subtracted
+  #[allow(clippy::too_many_arguments)]
+  pub(super) fn sub_flux_artificial<T: EoS>(&mut self, artificial: &ArtificialDissipation, hybrid: &HybridScheme,
+  rho: &[[f64; NJ]; NI], u: &[[f64; NJ]; NI], v: &[[f64; NJ]; NI],
+  e: &[[f64; NJ]; NI], phi: &[[[f64; NS]; NJ]; NI], phi_frozen: &[[[f64; NS]; NJ]; NI], coord: &GenStructCoord::<NI,NJ,NB>, eos: &T) {
+    let mixture = eos.is_mixture();
+    let (jst, vnr) = artificial.switches();
+    let cs = |i: usize, j: usize| eos.frozen(&phi_frozen[i][j]).calc_cs(&rho[i][j], &u[i][j], &v[i][j], &e[i][j]);
+
+    //--------------------i-direction---------------------
+    for i in 0..(NI-2*NB+1) {
+      for j in 0..(NJ-2*NB) {
+        let il = NB + i - 1;
+        let ir = NB + i;
+        let jc = NB + j;
+        if !hybrid.is_central(&self.arr_sensor[il][jc], &self.arr_sensor[ir][jc]) {
+          continue;
+        }
+        let s_a = 0.5 * (coord.s[NB+i-1][NB+j-1] + coord.s[NB+i-1][NB+j]);
+        let ixs_a = 0.5 * (coord.ixs[i][j+1] + coord.ixs[i+1][j+1]);
+        let iys_a = 0.5 * (coord.iys[i][j+1] + coord.iys[i+1][j+1]);
+        let u_a = 0.5 * (u[il][jc] + u[ir][jc]);
+        let v_a = 0.5 * (v[il][jc] + v[ir][jc]);
+        let mut vec_d = [0.0; NF];
+        if jst {
+          let cells = [il-1, il, ir, ir+1];
+          let lambda = (ixs_a * u_a + iys_a * v_a).abs() + 0.5 * (cs(il, jc) + cs(ir, jc)) * (ixs_a * ixs_a + iys_a * iys_a).sqrt();
+          artificial.calc_jst(&cells.map(|c| calc_q(&rho[c][jc], &u[c][jc], &v[c][jc], &e[c][jc], &phi[c][jc])),
+            &cells.map(|c| self.arr_p[c][jc]), &lambda, &mut vec_d);
+        }
+        if vnr {
+          let jxs_a = 0.5 * (coord.jxs[i][j+1] + coord.jxs[i+1][j+1]);
+          let jys_a = 0.5 * (coord.jys[i][j+1] + coord.jys[i+1][j+1]);
+          let u_i = fnd::central_diff3(&u[il-1][jc], &u[il][jc], &u[ir][jc], &u[ir+1][jc]);
+          let v_i = fnd::central_diff3(&v[il-1][jc], &v[il][jc], &v[ir][jc], &v[ir+1][jc]);
+          let u_j = 0.5 * (fnd::central_diff4(&u[il][jc-2], &u[il][jc-1], &u[il][jc+1], &u[il][jc+2])
+            + fnd::central_diff4(&u[ir][jc-2], &u[ir][jc-1], &u[ir][jc+1], &u[ir][jc+2]));
+          let v_j = 0.5 * (fnd::central_diff4(&v[il][jc-2], &v[il][jc-1], &v[il][jc+1], &v[il][jc+2])
+            + fnd::central_diff4(&v[ir][jc-2], &v[ir][jc-1], &v[ir][jc+1], &v[ir][jc+2]));
+          let div = (ixs_a * u_i + jxs_a * u_j + iys_a * v_i + jys_a * v_j) / s_a
+            + calc_v_r(&v_a, &(0.5 * (coord.y[il][jc] + coord.y[ir][jc])), &coord.axisymmetric);
+          let mut vec_dv = [0.0; NF];
+          artificial.calc_vnr(&(0.5 * (rho[il][jc] + rho[ir][jc])), &u_a, &v_a, &div, &ixs_a, &iys_a, &s_a, &mut vec_dv);
+          for k in 0..NF {
+            vec_d[k] += vec_dv[k];
+          }
+        }
+        for (k, d) in vec_d.iter().enumerate() {
+          self.arr_fi[i][j][k] -= d;
+          if mixture {
+            self.arr_fi_r[i][j][k] -= d;
+          }
+        }
+      }
+    }
+
+
+    //--------------------j-direction---------------------
+    for i in 0..(NI-2*NB) {
+      for j in 0..(NJ-2*NB+1) {
+        let ic = NB + i;
+        let jl = NB + j - 1;
+        let jr = NB + j;
+        let s_a = 0.5 * (coord.s[NB+i-1][NB+j-1] + coord.s[NB+i][NB+j-1]);
+        let jxs_a = 0.5 * (coord.jxs[i+1][j] + coord.jxs[i+1][j+1]);
+        let jys_a = 0.5 * (coord.jys[i+1][j] + coord.jys[i+1][j+1]);
+        if coord.is_axis_j(&i, &j) || !hybrid.is_central(&self.arr_sensor[ic][jl], &self.arr_sensor[ic][jr]) {
+          continue;
+        }
+        let u_a = 0.5 * (u[ic][jl] + u[ic][jr]);
+        let v_a = 0.5 * (v[ic][jl] + v[ic][jr]);
+        let mut vec_d = [0.0; NF];
+        if jst {
+          let cells = [jl-1, jl, jr, jr+1];
+          let lambda = (jxs_a * u_a + jys_a * v_a).abs() + 0.5 * (cs(ic, jl) + cs(ic, jr)) * (jxs_a * jxs_a + jys_a * jys_a).sqrt();
+          artificial.calc_jst(&cells.map(|c| calc_q(&rho[ic][c], &u[ic][c], &v[ic][c], &e[ic][c], &phi[ic][c])),
+            &cells.map(|c| self.arr_p[ic][c]), &lambda, &mut vec_d);
+        }
+        if vnr {
+          let ixs_a = 0.5 * (coord.ixs[i+1][j] + coord.ixs[i+1][j+1]);
+          let iys_a = 0.5 * (coord.iys[i+1][j] + coord.iys[i+1][j+1]);
+          let u_j = fnd::central_diff3(&u[ic][jl-1], &u[ic][jl], &u[ic][jr], &u[ic][jr+1]);
+          let v_j = fnd::central_diff3(&v[ic][jl-1], &v[ic][jl], &v[ic][jr], &v[ic][jr+1]);
+          let u_i = 0.5 * (fnd::central_diff4(&u[ic-2][jl], &u[ic-1][jl], &u[ic+1][jl], &u[ic+2][jl])
+            + fnd::central_diff4(&u[ic-2][jr], &u[ic-1][jr], &u[ic+1][jr], &u[ic+2][jr]));
+          let v_i = 0.5 * (fnd::central_diff4(&v[ic-2][jl], &v[ic-1][jl], &v[ic+1][jl], &v[ic+2][jl])
+            + fnd::central_diff4(&v[ic-2][jr], &v[ic-1][jr], &v[ic+1][jr], &v[ic+2][jr]));
+          let div = (ixs_a * u_i + jxs_a * u_j + iys_a * v_i + jys_a * v_j) / s_a
+            + calc_v_r(&v_a, &(0.5 * (coord.y[ic][jl] + coord.y[ic][jr])), &coord.axisymmetric);
+          let mut vec_dv = [0.0; NF];
+          artificial.calc_vnr(&(0.5 * (rho[ic][jl] + rho[ic][jr])), &u_a, &v_a, &div, &jxs_a, &jys_a, &s_a, &mut vec_dv);
+          for k in 0..NF {
+            vec_d[k] += vec_dv[k];
+          }
+        }
+        for (k, d) in vec_d.iter().enumerate() {
+          self.arr_fj[i][j][k] -= d;
+          if mixture {
+            self.arr_fj_r[i][j][k] -= d;
+          }
+        }
+      }
+    }
+  }
+}
+
+
+
+#[test]
+fn test_jst() {
+  // JST dissipation should vanish for linear distributions
+  let artificial = ArtificialDissipation::new("JST", &0.5, &(1.0 / 64.0), &1.0);
+  let q = [0.0, 1.0, 2.0, 3.0].map(|x| [1.0 + 0.1 * x; NF]);
+  let mut vec_d = [1.0; NF];
+  artificial.calc_jst(&q, &[1.0, 1.1, 1.2, 1.3], &2.0, &mut vec_d);
+  assert!(vec_d.iter().all(|d| d.abs() < 1.0e-14));
+  // and have the sign of the jump, so that subtracting it from flux is diffusive
+  let q = [[1.0; NF], [1.0; NF], [0.5; NF], [0.5; NF]];
+  artificial.calc_jst(&q, &[1.0, 1.0, 0.5, 0.5], &2.0, &mut vec_d);
+  assert!(vec_d.iter().all(|d| *d < 0.0));
+}
+
+
+
+#[test]
+fn test_artificial_bulk_viscosity() {
+  use super::super::super::{IdealEoS, IdealGas};
+  // artificial bulk viscosity of "VNR" on a periodic compression wave u = -a sin(2 pi x) with the central flux everywhere
+  // conserves mass, momentum and total energy, dissipates kinetic energy, and vanishes in expansion
+  const NI: usize = 24;
+  const NJ: usize = 12;
+  const NB: usize = 4;
+  let two_pi = 2.0 * std::f64::consts::PI;
+  let x = |i: usize| (i as f64 - NB as f64 + 0.5) / (NI - 2 * NB) as f64;
+  let y = |j: usize| (j as f64 - NB as f64 + 0.5) / (NI - 2 * NB) as f64;
+  let calc_rhs = |model: &'static str| {
+    let mut fluid = IdealGas::<NI,NJ,NB,_>::new_for_test(IdealEoS::new(&1.4, &1.0), |i, j| (x(i), y(j)), &false);
+    fluid.hybrid = HybridScheme::new("Kennedy_Gruber", "none", &0.1, &0.0);
+    fluid.artificial = ArtificialDissipation::new(model, &0.5, &(1.0 / 64.0), &1.0);
+    fluid.set_state(|i, _| (1.0, -0.2 * (two_pi * x(i)).sin(), 0.0, 1.0));
+    *fluid.calc_rhs_for_test("MUSCL_minmod_basic", "Roe_FDS")
+  };
+  let (arr_r, arr_r_vnr) = (calc_rhs("none"), calc_rhs("VNR"));
+  let (mut sum_mom, mut sum_e, mut sum_ke) = (0.0, 0.0, 0.0);
+  for (i, (arr_r_i, arr_r_vnr_i)) in arr_r.iter().zip(&arr_r_vnr).enumerate().take(NI-2*NB) {
+    let u = -0.2 * (two_pi * x(NB+i)).sin();
+    for (vec_r, vec_r_vnr) in arr_r_i.iter().zip(arr_r_vnr_i).take(NJ-2*NB) {
+      let d = [0, 1, 2, 3].map(|k| vec_r_vnr[k] - vec_r[k]);
+      assert!(d[0].abs() < 1.0e-15 && d[2].abs() < 1.0e-15);
+      // no bulk viscosity at faces in expansion 0.25 < x < 0.75 with the stencil of divergence
+      if (x(NB+i) - 0.5).abs() < 0.1 {
+        assert!(d.iter().all(|d| d.abs() < 1.0e-15), "bulk viscosity in expansion at x = {}", x(NB+i));
+      }
+      sum_mom += d[1];
+      sum_e += d[3];
+      sum_ke += u * d[1];
+    }
+  }
+  assert!(sum_mom.abs() < 1.0e-14 && sum_e.abs() < 1.0e-14, "not conservative: {:e} {:e}", sum_mom, sum_e);
+  assert!(sum_ke < -1.0e-6, "kinetic energy is not dissipated: {:e}", sum_ke);
+  // artificial dissipation without hybrid scheme is rejected instead of ignored
+  let result = std::panic::catch_unwind(|| {
+    let mut fluid = IdealGas::<NI,NJ,NB,_>::new_for_test(IdealEoS::new(&1.4, &1.0), |i, j| (x(i), y(j)), &false);
+    fluid.artificial = ArtificialDissipation::new("VNR", &0.5, &(1.0 / 64.0), &1.0);
+    fluid.initialize("", "", &false);
+  });
+  let message = result.expect_err("artificial dissipation without hybrid scheme is accepted");
+  assert!(message.downcast_ref::<&str>().is_some_and(|m| m.starts_with("Artificial dissipation")));
+}
@@ -1,2 +1,3 @@
+pub mod central;
 pub mod conserved;
 pub mod flux_scheme;
\ No newline at end of file
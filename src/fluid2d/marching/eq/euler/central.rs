@@ -0,0 +1,168 @@
+use super::super::super::super::{NE, NF, NS};
+
+
+
+/// logarithmic mean (a - b) / (ln a - ln b) of positive a and b
+/// evaluated with series expansion when a and b are close (Ismail and Roe)
+#[inline]
+fn log_mean(a: &f64, b: &f64) -> f64 {
+  let f = (a - b) / (a + b);
+  let f2 = f * f;
+  let g = if f2 < 1.0e-2 {
+    1.0 + f2 / 3.0 + f2 * f2 / 5.0 + f2 * f2 * f2 / 7.0 + f2 * f2 * f2 * f2 / 9.0
+  } else {
+    0.5 * (a / b).ln() / f
+  };
+  0.5 * (a + b) / g
+}
+
+
+
+/// two-point flux of split form between the cells a and b of the stencil in the direction of (ixs, iys)
+/// "Kennedy_Gruber": kinetic energy preserving flux of Kennedy and Gruber
+/// "Pirozzoli": kinetic energy preserving flux of Pirozzoli with total enthalpy
+/// "Ranocha": kinetic energy and entropy preserving flux of Ranocha,
+/// where gr is dp/d(rho e_int) (gamma - 1 for ideal gas) and entropy is preserved only for ideal gas
+#[inline]
+#[allow(clippy::too_many_arguments)]
+fn calc_split_flux(central: &str, rho: &[f64; 4], u: &[f64; 4], v: &[f64; 4], e: &[f64; 4], p: &[f64; 4], gr: &[f64; 4],
+  a: usize, b: usize, ixs: &f64, iys: &f64, vec_fc: &mut [f64; NE]) {
+  let avg = |q: &[f64; 4]| 0.5 * (q[a] + q[b]);
+  let bigu = ixs * avg(u) + iys * avg(v);
+  match central {
+    "Kennedy_Gruber" => {
+      let e_spec = 0.5 * (e[a] / rho[a] + e[b] / rho[b]);
+      let p_a = avg(p);
+      vec_fc[0] = avg(rho) * bigu;
+      vec_fc[1] = vec_fc[0] * avg(u) + ixs * p_a;
+      vec_fc[2] = vec_fc[0] * avg(v) + iys * p_a;
+      vec_fc[3] = vec_fc[0] * e_spec + p_a * bigu;
+    },
+    "Pirozzoli" => {
+      let h = 0.5 * ((e[a] + p[a]) / rho[a] + (e[b] + p[b]) / rho[b]);
+      let p_a = avg(p);
+      vec_fc[0] = avg(rho) * bigu;
+      vec_fc[1] = vec_fc[0] * avg(u) + ixs * p_a;
+      vec_fc[2] = vec_fc[0] * avg(v) + iys * p_a;
+      vec_fc[3] = vec_fc[0] * h;
+    },
+    "Ranocha" => {
+      let bigu_a = ixs * u[a] + iys * v[a];
+      let bigu_b = ixs * u[b] + iys * v[b];
+      let p_a = avg(p);
+      // p / rho averaged as the inverse of the logarithmic mean of rho / p
+      let p_rho = p[a] * p[b] / log_mean(&(rho[a] * p[b]), &(rho[b] * p[a]));
+      vec_fc[0] = log_mean(&rho[a], &rho[b]) * bigu;
+      vec_fc[1] = vec_fc[0] * avg(u) + ixs * p_a;
+      vec_fc[2] = vec_fc[0] * avg(v) + iys * p_a;
+      vec_fc[3] = vec_fc[0] * (0.5 * (u[a] * u[b] + v[a] * v[b]) + p_rho / avg(gr))
+        + 0.5 * (p[a] * bigu_b + p[b] * bigu_a);
+    },
+    _ => panic!("Central flux not specified."),
+  }
+}
+
+
+
+/// 4th order central flux at the face between the cells 1 and 2 of the stencil of 4 cells,
+/// as a linear combination of two-point fluxes of split form
+/// each two-point flux uses the metrices (ixs, iys) averaged over its two cell points,
+/// so that it is common to the faces it contributes to (flux differencing form),
+/// and kinetic energy (and entropy of "Ranocha") is preserved on non-uniform grids
+/// passive scalars are transported with the mass flux of each two-point flux and their averages
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub fn calc_central_flux(central: &str, rho: &[f64; 4], u: &[f64; 4], v: &[f64; 4], e: &[f64; 4], p: &[f64; 4], gr: &[f64; 4],
+  phi: &[[f64; NS]; 4], ixs: &[f64; 4], iys: &[f64; 4], vec_f: &mut [f64; NF]) {
+  *vec_f = [0.0; NF];
+  for (a, b, c) in [(1, 2, 4.0 / 3.0), (0, 2, -1.0 / 6.0), (1, 3, -1.0 / 6.0)] {
+    let mut vec_fc = [0.0; NE];
+    calc_split_flux(central, rho, u, v, e, p, gr, a, b, &(0.5 * (ixs[a] + ixs[b])), &(0.5 * (iys[a] + iys[b])), &mut vec_fc);
+    for k in 0..NE {
+      vec_f[k] += c * vec_fc[k];
+    }
+    for (f, (phi_a, phi_b)) in vec_f[NE..].iter_mut().zip(phi[a].iter().zip(&phi[b])) {
+      *f += c * vec_fc[0] * 0.5 * (phi_a + phi_b);
+    }
+  }
+}
+
+
+
+#[test]
+fn test_central_flux() {
+  use super::super::super::super::eos::EoS;
+  // central flux should return the physical flux for a uniform state
+  let eos = super::super::super::super::IdealEoS::new(&1.4, &1.0);
+  let (rho, u, v, e) = (1.2, 0.3, -0.4, 2.5);
+  let p = eos.calc_p(&rho, &u, &v, &e);
+  let (ixs, iys) = (0.8, 0.3);
+  let mut vec_fc = [0.0; NE];
+  super::conserved::calc_flux_conv(&rho, &u, &v, &e, &ixs, &iys, &eos, &mut vec_fc);
+  for central in ["Kennedy_Gruber", "Pirozzoli", "Ranocha"] {
+    let mut vec_f = [0.0; NF];
+    calc_central_flux(central, &[rho; 4], &[u; 4], &[v; 4], &[e; 4], &[p; 4], &[0.4; 4], &[[0.5; NS]; 4], &[ixs; 4], &[iys; 4], &mut vec_f);
+    for k in 0..NE {
+      assert!((vec_f[k] - vec_fc[k]).abs() < 1.0e-12, "{} is not consistent", central);
+    }
+  }
+  // logarithmic mean should be continuous across the switch to series expansion
+  for (a, b) in [(1.0, 1.0), (1.0, 1.0001), (1.0, 1.2), (1.0, 1.25), (0.5, 3.0)] {
+    let exact = if a == b { a } else { (b - a) / (f64::ln(b) - f64::ln(a)) };
+    assert!((log_mean(&a, &b) - exact).abs() < 1.0e-10 * exact);
+  }
+}
+
+
+
+#[test]
+fn test_central_conservation() {
+  use super::super::super::super::{eos::EoS, HybridScheme, IdealEoS, IdealGas};
+  // semi-discrete conservation of the central flux of hybrid scheme on a periodic non-uniform grid,
+  // given by the state and the coordinate periodic in both directions including the dummy cells
+  // kinetic energy for uniform pressure: sum of u . R_mom - |u|^2 / 2 R_rho vanishes
+  // entropy of "Ranocha": sum of the entropy variables times R vanishes
+  const NI: usize = 24;
+  const NJ: usize = 24;
+  const NB: usize = 4;
+  let two_pi = 2.0 * std::f64::consts::PI;
+  let xi = |i: usize| (i as f64 - NB as f64 + 0.5) / (NI - 2 * NB) as f64;
+  let eta = |j: usize| (j as f64 - NB as f64 + 0.5) / (NJ - 2 * NB) as f64;
+  // pseudo-random values in [0, 1) periodic in i and j
+  let rand = |i: usize, j: usize, k: usize| {
+    let (i, j) = ((i + NI - 2 * NB) % (NI - 2 * NB), (j + NJ - 2 * NB) % (NJ - 2 * NB));
+    ((i as f64 * 12.9898 + j as f64 * 78.233 + k as f64 * 37.719).sin() * 43758.5453).rem_euclid(1.0)
+  };
+  let dx = 1.0 / (NI - 2 * NB) as f64;
+  let xy = |i: usize, j: usize| {
+    let (xi, eta) = (xi(i), eta(j));
+    (xi + 0.04 * (two_pi * eta).sin() + 0.2 * dx * rand(i, j, 0), eta + 0.05 * (two_pi * xi).sin() + 0.2 * dx * rand(i, j, 1))
+  };
+  let gamma = 1.4_f64;
+  for (central, uniform_p) in [("Kennedy_Gruber", true), ("Pirozzoli", true), ("Ranocha", true), ("Ranocha", false)] {
+    let mut fluid = IdealGas::<NI,NJ,NB,_>::new_for_test(IdealEoS::new(&gamma, &1.0), xy, &false);
+    fluid.hybrid = HybridScheme::new(central, "none", &0.1, &0.0);
+    fluid.set_state(|i, j| (1.0 + 0.5 * rand(i, j, 2), rand(i, j, 3) - 0.5, rand(i, j, 4) - 0.5,
+      if uniform_p { 1.0 } else { 1.0 + 0.5 * rand(i, j, 5) }));
+    let arr_r = *fluid.calc_rhs_for_test("MUSCL_minmod_basic", "Roe_FDS");
+    let basic = &fluid.basic;
+    let (mut sum, mut scale) = (0.0, 0.0);
+    for (i, arr_r_i) in arr_r.iter().enumerate().take(NI-2*NB) {
+      for (j, vec_r) in arr_r_i.iter().enumerate().take(NJ-2*NB) {
+        let (rho, u, v, e) = (basic.rho[NB+i][NB+j], basic.u[NB+i][NB+j], basic.v[NB+i][NB+j], basic.e[NB+i][NB+j]);
+        let w = if uniform_p {
+          [-0.5 * (u * u + v * v), u, v, 0.0]
+        } else {
+          let p = fluid.eos.calc_p(&rho, &u, &v, &e);
+          let s = (p / rho.powf(gamma)).ln();
+          [(gamma - s) / (gamma - 1.0) - 0.5 * rho * (u * u + v * v) / p, rho * u / p, rho * v / p, -rho / p]
+        };
+        for k in 0..4 {
+          sum += w[k] * vec_r[k];
+          scale += (w[k] * vec_r[k]).abs();
+        }
+      }
+    }
+    assert!(sum.abs() < 1.0e-12 * scale, "{} {} {:e} {:e}", central, uniform_p, sum, scale);
+  }
+}
@@ -1,12 +1,13 @@
-const NF: usize = 4;
 use super::super::super::super::eos::EoS;
 
 
 
 /// construct conservative var from basic var
+/// only the components of Euler system are set
+/// s includes radial weighting for axisymmetric flow
 #[inline]
 pub fn calc_conservative(rho: &f64, u: &f64, v: &f64, e: &f64, 
-  s: &f64, vec_q: &mut [f64; NF]) {
+  s: &f64, vec_q: &mut [f64]) {
     vec_q[0] = rho * s;
     vec_q[1] = rho * u * s;
     vec_q[2] = rho * v * s;
@@ -16,9 +17,11 @@ pub fn calc_conservative(rho: &f64, u: &f64, v: &f64, e: &f64,
 
 
 /// construct convection flux from basic var
+/// only the components of Euler system are set
 #[inline]
+#[allow(clippy::too_many_arguments)]
 pub fn calc_flux_conv<T: EoS>(rho: &f64, u: &f64, v: &f64, e:&f64,
-  ixs: &f64, iys: &f64, eos: &T, vec_fc: &mut[f64; NF]) {
+  ixs: &f64, iys: &f64, eos: &T, vec_fc: &mut [f64]) {
     let bigu = ixs * u + iys * v;
     let p = eos.calc_p(rho, u, v, e);
     vec_fc[0] = rho * bigu;
@@ -31,8 +34,10 @@ pub fn calc_flux_conv<T: EoS>(rho: &f64, u: &f64, v: &f64, e:&f64,
 
   
 /// construct basic var from conservative var
+/// only the components of Euler system are used
+/// s includes radial weighting for axisymmetric flow
 #[inline]
-pub fn calc_basic(vec_q: &[f64; NF], s: &f64, 
+pub fn calc_basic(vec_q: &[f64], s: &f64, 
   rho: &mut f64, u: &mut f64, v: &mut f64, e: &mut f64) {
     *rho = vec_q[0] / s;
     *u = vec_q[1] / *rho / s;
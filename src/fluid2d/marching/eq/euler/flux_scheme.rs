@@ -1,17 +1,33 @@
-const NF: usize = 4;
-use super::super::super::super::eos::EoS;
+/// number of equations of Euler system
+const NE: usize = 4;
+use super::super::super::super::eos::{EoS, calc_eigen_general};
 
 use super::super::fnd;
 
 
 
 /// calc numerical flux with scheme selected as the first arg
+/// global Lax-Friedrichs needs the signal speed over the domain, and is given by lax_friedrichs
 #[inline]
+#[allow(clippy::too_many_arguments)]
 pub fn calc_num_flux<T: EoS>(flux_scheme: &str, rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64,
   rho_r: &f64, u_r: &f64, v_r: &f64, e_r: &f64, 
-  ixs: &f64, iys: &f64, s: &f64, eos: &T, vec_fc: &mut [f64; NF]) {
+  ixs: &f64, iys: &f64, s: &f64, eos: &T, vec_fc: &mut [f64; NE]) {
     match flux_scheme {
-      "Roe_FDS" => roe_fds(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, ixs, iys, s, eos, vec_fc),
+      "Roe_FDS" => roe_fds(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, ixs, iys, s, "none", eos, vec_fc),
+      "Roe_FDS_Harten" => roe_fds(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, ixs, iys, s, "Harten", eos, vec_fc),
+      "Roe_FDS_Harten_Hyman" => roe_fds(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, ixs, iys, s, "Harten_Hyman", eos, vec_fc),
+      "Roe_FDS_Thornber" => roe_fds_thornber(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, ixs, iys, s, eos, vec_fc),
+      "Rotated_RHLL" => rotated_rhll(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, ixs, iys, eos, vec_fc),
+      "Steger_Warming" => steger_warming(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, ixs, iys, s, eos, vec_fc),
+      "Van_Leer" => van_leer(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, ixs, iys, eos, vec_fc),
+      "Rusanov" => lax_friedrichs(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, ixs, iys, s, &None, eos, vec_fc),
+      "HLL" => hll(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, ixs, iys, s, eos, vec_fc),
+      "HLLC" => hllc(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, ixs, iys, s, eos, vec_fc),
+      "AUSM+" => ausm_plus(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, ixs, iys, eos, vec_fc),
+      "AUSM+-up" => ausm_plus_up(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, ixs, iys, eos, vec_fc),
+      "SLAU" => slau(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, ixs, iys, &false, eos, vec_fc),
+      "SLAU2" => slau(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, ixs, iys, &true, eos, vec_fc),
       _ => panic!("Flux scheme not specified."),
     }
   }
@@ -21,6 +37,7 @@ pub fn calc_num_flux<T: EoS>(flux_scheme: &str, rho_l: &f64, u_l: &f64, v_l: &f6
 /// reconstruct the cell-boundary values
 /// with MUSCL-minmod adapted for basic variables
 #[inline]
+#[allow(clippy::too_many_arguments)]
 pub fn reconst_by_basic_muscl(
 rho0: &f64, rho1: &f64, rho2: &f64, rho3: &f64,
 u0: &f64, u1: &f64, u2: &f64, u3: &f64,
@@ -39,6 +56,7 @@ rho_r: &mut f64, u_r: &mut f64, v_r: &mut f64, e_r: &mut f64) {
 /// reconstruct the cell-boundary values
 /// with MP5 adapted for basic variables
 #[inline]
+#[allow(clippy::too_many_arguments)]
 pub fn reconst_by_basic_mp5(
 rho0: &f64, rho1: &f64, rho2: &f64, rho3: &f64, rho4: &f64, rho5: &f64,
 u0: &f64, u1: &f64, u2: &f64, u3: &f64, u4: &f64, u5: &f64,
@@ -52,11 +70,16 @@ rho_r: &mut f64, u_r: &mut f64, v_r: &mut f64, e_r: &mut f64) {
   fnd::mp5(e0, e1, e2, e3, e4, e5, e_l, e_r);
 }
 
-/// calc Roe average
+/// calc Roe average of velocity, total specific enthalpy and sound-speed
+/// with derivatives of pressure chi and gr for calc_eigen_general
+/// averages of dp/drho and dp/d(rho e_int) are projected so that
+/// p_r - p_l = dp/drho (rho_r - rho_l) + dp/d(rho e_int) (rho_r e_int_r - rho_l e_int_l),
+/// keeping the Roe property for non-ideal gases
 #[inline]
+#[allow(clippy::too_many_arguments)]
 fn roe_average<T: EoS>(rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64,
   rho_r: &f64, u_r: &f64, v_r: &f64, e_r: &f64, eos: &T,
-  rho_a: &mut f64, u_a: &mut f64, v_a: &mut f64, e_a: &mut f64) {
+  u_a: &mut f64, v_a: &mut f64, h_a: &mut f64, cs_a: &mut f64, chi_a: &mut f64, gr_a: &mut f64) {
   let p_l = eos.calc_p(rho_l, u_l, v_l, e_l);
   let p_r = eos.calc_p(rho_r, u_r, v_r, e_r);
   let h_l = (e_l + p_l) / rho_l;
@@ -64,44 +87,792 @@ fn roe_average<T: EoS>(rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64,
   let sqr_l = rho_l.sqrt();
   let sqr_r = rho_r.sqrt();
 
-  *rho_a = sqr_l * sqr_r;
   *u_a = (sqr_l * u_l + sqr_r * u_r) / (sqr_l + sqr_r);
   *v_a = (sqr_l * v_l + sqr_r * v_r) / (sqr_l + sqr_r);
-  let h_a = (sqr_l * h_l + sqr_r * h_r) / (sqr_l + sqr_r);
-  *e_a = eos.calc_e(rho_a, u_a, v_a, &h_a);
+  *h_a = (sqr_l * h_l + sqr_r * h_r) / (sqr_l + sqr_r);
+  let ke_a = 0.5 * (*u_a * *u_a + *v_a * *v_a);
+
+  // arithmetic averages of pressure derivatives
+  let (dpdr_l, dpde_l) = eos.calc_dp(rho_l, u_l, v_l, e_l);
+  let (dpdr_r, dpde_r) = eos.calc_dp(rho_r, u_r, v_r, e_r);
+  let mut dpdr_a = 0.5 * (dpdr_l + dpdr_r);
+  let mut dpde_a = 0.5 * (dpde_l + dpde_r);
+  let cs2_h = 0.5 * (dpdr_l + dpde_l * (h_l - 0.5 * (u_l * u_l + v_l * v_l))
+    + dpdr_r + dpde_r * (h_r - 0.5 * (u_r * u_r + v_r * v_r)));
+  // projection minimizing the changes scaled by cs^2 and dp/d(rho e_int)
+  let d_rho = rho_r - rho_l;
+  let d_rhoe = (e_r - 0.5 * rho_r * (u_r * u_r + v_r * v_r)) - (e_l - 0.5 * rho_l * (u_l * u_l + v_l * v_l));
+  let res = (p_r - p_l) - dpdr_a * d_rho - dpde_a * d_rhoe;
+  let w_r = cs2_h * cs2_h * d_rho;
+  let w_e = dpde_a * dpde_a * d_rhoe;
+  let den = w_r * d_rho + w_e * d_rhoe;
+  let tol = 1.0e-10 * (p_l.abs() + p_r.abs() + cs2_h * (rho_l + rho_r));
+  if den > tol * tol {
+    let cs2_p = dpdr_a + res * w_r / den + (dpde_a + res * w_e / den) * (*h_a - ke_a);
+    if cs2_p > 0.0 {
+      dpdr_a += res * w_r / den;
+      dpde_a += res * w_e / den;
+    }
+  }
+  *cs_a = (dpdr_a + dpde_a * (*h_a - ke_a)).sqrt();
+  *chi_a = dpdr_a + dpde_a * ke_a;
+  *gr_a = dpde_a;
 }
+
+
+
+/// absolute value of eigen value
+/// smoothed by parabola within |lam| < delta (Harten's entropy fix)
+#[inline]
+fn abs_entropy_fixed(lam: &f64, delta: &f64) -> f64 {
+  if lam.abs() < *delta {
+    0.5 * (lam * lam + delta * delta) / delta
+  } else {
+    lam.abs()
+  }
+}
+
+
+
 /// calc convective numerical flux using classical Roe-type FDS scheme
+/// with entropy fix for the acoustic waves selected by entropy_fix
+/// ("none", "Harten" or "Harten_Hyman")
+#[allow(clippy::too_many_arguments)]
 fn roe_fds<T: EoS>(rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64,
   rho_r: &f64, u_r: &f64, v_r: &f64, e_r: &f64,
-  ixs: &f64, iys: &f64, s: &f64, eos: &T, vec_fc: &mut [f64; NF]) {
+  ixs: &f64, iys: &f64, s: &f64, entropy_fix: &str, eos: &T, vec_fc: &mut [f64; NE]) {
   let ix = ixs / s;
   let iy = iys / s;
-  let mut rho_a = 0.0;
   let mut u_a = 0.0;
   let mut v_a = 0.0;
-  let mut e_a = 0.0;
+  let mut h_a = 0.0;
+  let mut cs_a = 0.0;
+  let mut chi_a = 0.0;
+  let mut gr_a = 0.0;
   roe_average(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, eos,
-    &mut rho_a, &mut u_a, &mut v_a, &mut e_a);
-  let mut vec_q_l = [0.0; NF];
+    &mut u_a, &mut v_a, &mut h_a, &mut cs_a, &mut chi_a, &mut gr_a);
+  let mut vec_q_l = [0.0; NE];
   super::conserved::calc_conservative(rho_l, u_l, v_l, e_l, s, &mut vec_q_l);
-  let mut vec_q_r = [0.0; NF];
+  let mut vec_q_r = [0.0; NE];
  super::conserved::calc_conservative(rho_r, u_r, v_r, e_r, s, &mut vec_q_r);
-  let mut vec_f_l = [0.0; NF];
+  let mut vec_f_l = [0.0; NE];
   super::conserved::calc_flux_conv::<T>(rho_l, u_l, v_l, e_l, ixs, iys, eos, &mut vec_f_l);
-  let mut vec_f_r = [0.0; NF];
+  let mut vec_f_r = [0.0; NE];
   super::conserved::calc_flux_conv::<T>(rho_r, u_r, v_r, e_r, ixs, iys, eos, &mut vec_f_r);
-  let mut dia_lam = [0.0; NF];
-  let mut mat_r = [[0.0; NF]; NF];
-  let mut mat_rinv = [[0.0; NF]; NF];
-  eos.calc_eigen(&rho_a, &u_a, &v_a, &e_a, &ix, &iy, &mut dia_lam, &mut mat_r, &mut mat_rinv);
+  let mut dia_lam = [0.0; NE];
+  let mut mat_r = [[0.0; NE]; NE];
+  let mut mat_rinv = [[0.0; NE]; NE];
+  calc_eigen_general(&u_a, &v_a, &cs_a, &h_a, &chi_a, &gr_a, &ix, &iy, &mut dia_lam, &mut mat_r, &mut mat_rinv);
+  let mut dia_lam_abs = [0.0; NE];
+  for j in 0..NE {
+    dia_lam_abs[j] = dia_lam[j].abs();
+  }
+  match entropy_fix {
+    "none" => (),
+    "Harten" => {
+      // width of the fix proportional to the spectral radius
+      let eps = 0.1;
+      let delta = eps * (dia_lam[1].abs() + 0.5 * (dia_lam[2] - dia_lam[0]));
+      dia_lam_abs[0] = abs_entropy_fixed(&dia_lam[0], &delta);
+      dia_lam_abs[2] = abs_entropy_fixed(&dia_lam[2], &delta);
+    },
+    "Harten_Hyman" => {
+      // width of the fix from the spread of the characteristic speeds
+      let sqr = (ix * ix + iy * iy).sqrt();
+      let cs_l = eos.calc_cs(rho_l, u_l, v_l, e_l);
+      let cs_r = eos.calc_cs(rho_r, u_r, v_r, e_r);
+      let bigu_l = ix * u_l + iy * v_l;
+      let bigu_r = ix * u_r + iy * v_r;
+      let delta = 0.0_f64.max(dia_lam[0] - (bigu_l - cs_l * sqr)).max((bigu_r - cs_r * sqr) - dia_lam[0]);
+      dia_lam_abs[0] = abs_entropy_fixed(&dia_lam[0], &delta);
+      let delta = 0.0_f64.max(dia_lam[2] - (bigu_l + cs_l * sqr)).max((bigu_r + cs_r * sqr) - dia_lam[2]);
+      dia_lam_abs[2] = abs_entropy_fixed(&dia_lam[2], &delta);
+    },
+    _ => panic!("Entropy fix not specified."),
+  }
 
-  for i in 0..NF {
+  for i in 0..NE {
     vec_fc[i] = vec_f_r[i] + vec_f_l[i];
-    for j in 0..NF {
-      for k in 0.. NF {
-        vec_fc[i] -= mat_r[i][j] * (dia_lam[j].abs()) * mat_rinv[j][k] * (vec_q_r[k] - vec_q_l[k]);
+    for j in 0..NE {
+      for k in 0.. NE {
+        vec_fc[i] -= mat_r[i][j] * dia_lam_abs[j] * mat_rinv[j][k] * (vec_q_r[k] - vec_q_l[k]);
       }
     }
     vec_fc[i] *= 0.5;
   }
-}
\ No newline at end of file
+}
+
+
+
+/// calc convective numerical flux using Roe-type FDS scheme
+/// with low-Mach correction of Thornber et al. (2008),
+/// where the velocity jump is scaled by the local Mach number
+#[allow(clippy::too_many_arguments)]
+fn roe_fds_thornber<T: EoS>(rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64,
+  rho_r: &f64, u_r: &f64, v_r: &f64, e_r: &f64,
+  ixs: &f64, iys: &f64, s: &f64, eos: &T, vec_fc: &mut [f64; NE]) {
+  let p_l = eos.calc_p(rho_l, u_l, v_l, e_l);
+  let p_r = eos.calc_p(rho_r, u_r, v_r, e_r);
+  let mach_l = (u_l * u_l + v_l * v_l).sqrt() / eos.calc_cs(rho_l, u_l, v_l, e_l);
+  let mach_r = (u_r * u_r + v_r * v_r).sqrt() / eos.calc_cs(rho_r, u_r, v_r, e_r);
+  let z = mach_l.max(mach_r).min(1.0);
+  // reconstructed velocities with reduced jump
+  let u_lc = 0.5 * (u_l + u_r) + 0.5 * z * (u_l - u_r);
+  let u_rc = 0.5 * (u_l + u_r) - 0.5 * z * (u_l - u_r);
+  let v_lc = 0.5 * (v_l + v_r) + 0.5 * z * (v_l - v_r);
+  let v_rc = 0.5 * (v_l + v_r) - 0.5 * z * (v_l - v_r);
+  // total energies keeping pressures unchanged
+  let e_lc = eos.calc_e_wp(rho_l, &u_lc, &v_lc, &p_l);
+  let e_rc = eos.calc_e_wp(rho_r, &u_rc, &v_rc, &p_r);
+  roe_fds(rho_l, &u_lc, &v_lc, &e_lc, rho_r, &u_rc, &v_rc, &e_rc, ixs, iys, s, "none", eos, vec_fc);
+}
+
+
+
+/// calc convective numerical flux using Rotated-RHLL scheme,
+/// which applies HLL in the direction of velocity difference
+/// (normal to shocks) and Roe in the direction perpendicular to it
+#[allow(clippy::too_many_arguments)]
+fn rotated_rhll<T: EoS>(rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64,
+  rho_r: &f64, u_r: &f64, v_r: &f64, e_r: &f64,
+  ixs: &f64, iys: &f64, eos: &T, vec_fc: &mut [f64; NE]) {
+  let area = (ixs * ixs + iys * iys).sqrt();
+  let nx = ixs / area;
+  let ny = iys / area;
+  // n1: direction of velocity difference, or tangential to the cell-boundary
+  let du = u_r - u_l;
+  let dv = v_r - v_l;
+  let abs_dq = (du * du + dv * dv).sqrt();
+  let (mut nx1, mut ny1) = if abs_dq > 1.0e-12 {
+    (du / abs_dq, dv / abs_dq)
+  } else {
+    (-ny, nx)
+  };
+  let mut alp1 = nx * nx1 + ny * ny1;
+  if alp1 < 0.0 {
+    nx1 = -nx1;
+    ny1 = -ny1;
+    alp1 = -alp1;
+  }
+  // n2: perpendicular to n1
+  let mut nx2 = -ny1;
+  let mut ny2 = nx1;
+  let mut alp2 = nx * nx2 + ny * ny2;
+  if alp2 < 0.0 {
+    nx2 = -nx2;
+    ny2 = -ny2;
+    alp2 = -alp2;
+  }
+  // HLL wave speeds in n1
+  let mut sp_l = 0.0;
+  let mut sp_r = 0.0;
+  wave_speeds_einfeldt(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, &nx1, &ny1, eos,
+    &mut sp_l, &mut sp_r);
+  let sp_lm = sp_l.min(0.0);
+  let sp_rp = sp_r.max(0.0);
+  // Roe eigen system in n2
+  let mut u_a = 0.0;
+  let mut v_a = 0.0;
+  let mut h_a = 0.0;
+  let mut cs_a = 0.0;
+  let mut chi_a = 0.0;
+  let mut gr_a = 0.0;
+  roe_average(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, eos,
+    &mut u_a, &mut v_a, &mut h_a, &mut cs_a, &mut chi_a, &mut gr_a);
+  let mut dia_lam = [0.0; NE];
+  let mut mat_r = [[0.0; NE]; NE];
+  let mut mat_rinv = [[0.0; NE]; NE];
+  calc_eigen_general(&u_a, &v_a, &cs_a, &h_a, &chi_a, &gr_a, &nx2, &ny2, &mut dia_lam, &mut mat_r, &mut mat_rinv);
+  // modified wave speeds combining HLL (n1) and Roe (n2)
+  let mut dia_lam_mod = [0.0; NE];
+  for j in 0..NE {
+    dia_lam_mod[j] = alp2 * dia_lam[j].abs()
+      - (alp2 * (sp_rp + sp_lm) * dia_lam[j] + 2.0 * alp1 * sp_rp * sp_lm) / (sp_rp - sp_lm);
+  }
+  let mut vec_q_l = [0.0; NE];
+  super::conserved::calc_conservative(rho_l, u_l, v_l, e_l, &1.0, &mut vec_q_l);
+  let mut vec_q_r = [0.0; NE];
+  super::conserved::calc_conservative(rho_r, u_r, v_r, e_r, &1.0, &mut vec_q_r);
+  let mut vec_f_l = [0.0; NE];
+  super::conserved::calc_flux_conv::<T>(rho_l, u_l, v_l, e_l, &nx, &ny, eos, &mut vec_f_l);
+  let mut vec_f_r = [0.0; NE];
+  super::conserved::calc_flux_conv::<T>(rho_r, u_r, v_r, e_r, &nx, &ny, eos, &mut vec_f_r);
+
+  for i in 0..NE {
+    vec_fc[i] = (sp_rp * vec_f_l[i] - sp_lm * vec_f_r[i]) / (sp_rp - sp_lm);
+    for j in 0..NE {
+      for k in 0..NE {
+        vec_fc[i] -= 0.5 * mat_r[i][j] * dia_lam_mod[j] * mat_rinv[j][k] * (vec_q_r[k] - vec_q_l[k]);
+      }
+    }
+    vec_fc[i] *= area;
+  }
+}
+
+
+
+/// estimate the fastest left/right-running signal speeds
+/// in the contravariant form (Einfeldt's estimates with Roe average)
+#[inline]
+#[allow(clippy::too_many_arguments)]
+fn wave_speeds_einfeldt<T: EoS>(rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64,
+  rho_r: &f64, u_r: &f64, v_r: &f64, e_r: &f64,
+  ix: &f64, iy: &f64, eos: &T, sp_l: &mut f64, sp_r: &mut f64) {
+  let mut u_a = 0.0;
+  let mut v_a = 0.0;
+  let mut h_a = 0.0;
+  let mut cs_a = 0.0;
+  let mut chi_a = 0.0;
+  let mut gr_a = 0.0;
+  roe_average(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, eos,
+    &mut u_a, &mut v_a, &mut h_a, &mut cs_a, &mut chi_a, &mut gr_a);
+  let sqr = (ix * ix + iy * iy).sqrt();
+  let cs_l = eos.calc_cs(rho_l, u_l, v_l, e_l);
+  let cs_r = eos.calc_cs(rho_r, u_r, v_r, e_r);
+  let bigu_l = ix * u_l + iy * v_l;
+  let bigu_r = ix * u_r + iy * v_r;
+  let bigu_a = ix * u_a + iy * v_a;
+  *sp_l = (bigu_l - cs_l * sqr).min(bigu_a - cs_a * sqr);
+  *sp_r = (bigu_r + cs_r * sqr).max(bigu_a + cs_a * sqr);
+}
+
+
+
+/// calc convective numerical flux using HLL approximate Riemann solver
+#[allow(clippy::too_many_arguments)]
+fn hll<T: EoS>(rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64,
+  rho_r: &f64, u_r: &f64, v_r: &f64, e_r: &f64,
+  ixs: &f64, iys: &f64, s: &f64, eos: &T, vec_fc: &mut [f64; NE]) {
+  let ix = ixs / s;
+  let iy = iys / s;
+  let mut sp_l = 0.0;
+  let mut sp_r = 0.0;
+  wave_speeds_einfeldt(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, &ix, &iy, eos,
+    &mut sp_l, &mut sp_r);
+  let mut vec_f_l = [0.0; NE];
+  super::conserved::calc_flux_conv::<T>(rho_l, u_l, v_l, e_l, ixs, iys, eos, &mut vec_f_l);
+  let mut vec_f_r = [0.0; NE];
+  super::conserved::calc_flux_conv::<T>(rho_r, u_r, v_r, e_r, ixs, iys, eos, &mut vec_f_r);
+  if sp_l >= 0.0 {
+    *vec_fc = vec_f_l;
+    return;
+  }
+  if sp_r <= 0.0 {
+    *vec_fc = vec_f_r;
+    return;
+  }
+  let mut vec_q_l = [0.0; NE];
+  super::conserved::calc_conservative(rho_l, u_l, v_l, e_l, s, &mut vec_q_l);
+  let mut vec_q_r = [0.0; NE];
+  super::conserved::calc_conservative(rho_r, u_r, v_r, e_r, s, &mut vec_q_r);
+  for i in 0..NE {
+    vec_fc[i] = (sp_r * vec_f_l[i] - sp_l * vec_f_r[i]
+      + sp_l * sp_r * (vec_q_r[i] - vec_q_l[i])) / (sp_r - sp_l);
+  }
+}
+
+
+
+/// construct HLLC intermediate state on one side of the contact
+#[inline]
+#[allow(clippy::too_many_arguments)]
+fn hllc_star_state(rho: &f64, u: &f64, v: &f64, e: &f64, p: &f64,
+  ix: &f64, iy: &f64, s: &f64, sp: &f64, sp_m: &f64, vec_q_star: &mut [f64; NE]) {
+  let sqr2 = ix * ix + iy * iy;
+  let bigu = ix * u + iy * v;
+  let coeff = rho * (sp - bigu) / (sp - sp_m) * s;
+  vec_q_star[0] = coeff;
+  vec_q_star[1] = coeff * (u + (sp_m - bigu) * ix / sqr2);
+  vec_q_star[2] = coeff * (v + (sp_m - bigu) * iy / sqr2);
+  vec_q_star[3] = coeff * (e / rho
+    + (sp_m - bigu) / sqr2 * (sp_m + p * sqr2 / (rho * (sp - bigu))));
+}
+
+
+
+/// calc convective numerical flux using HLLC approximate Riemann solver
+#[allow(clippy::too_many_arguments)]
+fn hllc<T: EoS>(rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64,
+  rho_r: &f64, u_r: &f64, v_r: &f64, e_r: &f64,
+  ixs: &f64, iys: &f64, s: &f64, eos: &T, vec_fc: &mut [f64; NE]) {
+  let ix = ixs / s;
+  let iy = iys / s;
+  let mut sp_l = 0.0;
+  let mut sp_r = 0.0;
+  wave_speeds_einfeldt(rho_l, u_l, v_l, e_l, rho_r, u_r, v_r, e_r, &ix, &iy, eos,
+    &mut sp_l, &mut sp_r);
+  let mut vec_f_l = [0.0; NE];
+  super::conserved::calc_flux_conv::<T>(rho_l, u_l, v_l, e_l, ixs, iys, eos, &mut vec_f_l);
+  let mut vec_f_r = [0.0; NE];
+  super::conserved::calc_flux_conv::<T>(rho_r, u_r, v_r, e_r, ixs, iys, eos, &mut vec_f_r);
+  if sp_l >= 0.0 {
+    *vec_fc = vec_f_l;
+    return;
+  }
+  if sp_r <= 0.0 {
+    *vec_fc = vec_f_r;
+    return;
+  }
+  // speed of the contact wave
+  let p_l = eos.calc_p(rho_l, u_l, v_l, e_l);
+  let p_r = eos.calc_p(rho_r, u_r, v_r, e_r);
+  let sqr2 = ix * ix + iy * iy;
+  let bigu_l = ix * u_l + iy * v_l;
+  let bigu_r = ix * u_r + iy * v_r;
+  let sp_m = (sqr2 * (p_r - p_l)
+    + rho_l * bigu_l * (sp_l - bigu_l) - rho_r * bigu_r * (sp_r - bigu_r))
+    / (rho_l * (sp_l - bigu_l) - rho_r * (sp_r - bigu_r));
+  // flux of the star region on the upwind side of the contact
+  let mut vec_q = [0.0; NE];
+  let mut vec_q_star = [0.0; NE];
+  if sp_m >= 0.0 {
+    super::conserved::calc_conservative(rho_l, u_l, v_l, e_l, s, &mut vec_q);
+    hllc_star_state(rho_l, u_l, v_l, e_l, &p_l, &ix, &iy, s, &sp_l, &sp_m, &mut vec_q_star);
+    for i in 0..NE {
+      vec_fc[i] = vec_f_l[i] + sp_l * (vec_q_star[i] - vec_q[i]);
+    }
+  } else {
+    super::conserved::calc_conservative(rho_r, u_r, v_r, e_r, s, &mut vec_q);
+    hllc_star_state(rho_r, u_r, v_r, e_r, &p_r, &ix, &iy, s, &sp_r, &sp_m, &mut vec_q_star);
+    for i in 0..NE {
+      vec_fc[i] = vec_f_r[i] + sp_r * (vec_q_star[i] - vec_q[i]);
+    }
+  }
+}
+
+
+
+/// 4th order split Mach number polynomial M+ for AUSM-family
+#[inline]
+fn split_mach_plus(m: &f64, beta: &f64) -> f64 {
+  if m.abs() >= 1.0 {
+    0.5 * (m + m.abs())
+  } else {
+    0.25 * (m + 1.0) * (m + 1.0) + beta * (m * m - 1.0) * (m * m - 1.0)
+  }
+}
+
+
+
+/// 4th order split Mach number polynomial M- for AUSM-family
+#[inline]
+fn split_mach_minus(m: &f64, beta: &f64) -> f64 {
+  if m.abs() >= 1.0 {
+    0.5 * (m - m.abs())
+  } else {
+    -0.25 * (m - 1.0) * (m - 1.0) - beta * (m * m - 1.0) * (m * m - 1.0)
+  }
+}
+
+
+
+/// 5th order split pressure polynomial P+ for AUSM-family
+#[inline]
+fn split_pressure_plus(m: &f64, alpha: &f64) -> f64 {
+  if m.abs() >= 1.0 {
+    0.5 * (1.0 + m.signum())
+  } else {
+    0.25 * (m + 1.0) * (m + 1.0) * (2.0 - m) + alpha * m * (m * m - 1.0) * (m * m - 1.0)
+  }
+}
+
+
+
+/// 5th order split pressure polynomial P- for AUSM-family
+#[inline]
+fn split_pressure_minus(m: &f64, alpha: &f64) -> f64 {
+  if m.abs() >= 1.0 {
+    0.5 * (1.0 - m.signum())
+  } else {
+    0.25 * (m - 1.0) * (m - 1.0) * (2.0 + m) - alpha * m * (m * m - 1.0) * (m * m - 1.0)
+  }
+}
+
+
+
+/// assemble AUSM-type flux from mass flux and pressure flux
+/// evaluated per unit area of the cell-boundary
+#[inline]
+#[allow(clippy::too_many_arguments)]
+fn assemble_ausm_flux(rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64, p_l: &f64,
+  rho_r: &f64, u_r: &f64, v_r: &f64, e_r: &f64, p_r: &f64,
+  mdot: &f64, p_half: &f64, ixs: &f64, iys: &f64, vec_fc: &mut [f64; NE]) {
+  let mdot_p = 0.5 * (mdot + mdot.abs());
+  let mdot_m = 0.5 * (mdot - mdot.abs());
+  let h_l = (e_l + p_l) / rho_l;
+  let h_r = (e_r + p_r) / rho_r;
+  let area = (ixs * ixs + iys * iys).sqrt();
+  vec_fc[0] = area * (mdot_p + mdot_m);
+  vec_fc[1] = area * (mdot_p * u_l + mdot_m * u_r) + ixs * p_half;
+  vec_fc[2] = area * (mdot_p * v_l + mdot_m * v_r) + iys * p_half;
+  vec_fc[3] = area * (mdot_p * h_l + mdot_m * h_r);
+}
+
+
+
+/// calc convective numerical flux using AUSM+ scheme
+#[allow(clippy::too_many_arguments)]
+fn ausm_plus<T: EoS>(rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64,
+  rho_r: &f64, u_r: &f64, v_r: &f64, e_r: &f64,
+  ixs: &f64, iys: &f64, eos: &T, vec_fc: &mut [f64; NE]) {
+  let alpha = 3.0 / 16.0;
+  let beta = 1.0 / 8.0;
+  let area = (ixs * ixs + iys * iys).sqrt();
+  let nx = ixs / area;
+  let ny = iys / area;
+  let p_l = eos.calc_p(rho_l, u_l, v_l, e_l);
+  let p_r = eos.calc_p(rho_r, u_r, v_r, e_r);
+  let cs_a = 0.5 * (eos.calc_cs(rho_l, u_l, v_l, e_l) + eos.calc_cs(rho_r, u_r, v_r, e_r));
+  let mach_l = (nx * u_l + ny * v_l) / cs_a;
+  let mach_r = (nx * u_r + ny * v_r) / cs_a;
+  let mach_a = split_mach_plus(&mach_l, &beta) + split_mach_minus(&mach_r, &beta);
+  let mdot = cs_a * (0.5 * (mach_a + mach_a.abs()) * rho_l + 0.5 * (mach_a - mach_a.abs()) * rho_r);
+  let p_half = split_pressure_plus(&mach_l, &alpha) * p_l + split_pressure_minus(&mach_r, &alpha) * p_r;
+  assemble_ausm_flux(rho_l, u_l, v_l, e_l, &p_l, rho_r, u_r, v_r, e_r, &p_r,
+    &mdot, &p_half, ixs, iys, vec_fc);
+}
+
+
+
+/// calc convective numerical flux using AUSM+-up scheme
+#[allow(clippy::too_many_arguments)]
+fn ausm_plus_up<T: EoS>(rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64,
+  rho_r: &f64, u_r: &f64, v_r: &f64, e_r: &f64,
+  ixs: &f64, iys: &f64, eos: &T, vec_fc: &mut [f64; NE]) {
+  // coefficients of pressure/velocity diffusion
+  let kp = 0.25;
+  let ku = 0.75;
+  let sigma = 1.0;
+  // cut-off Mach number for the low-Mach scaling
+  let mach_co = 0.1;
+  let beta = 1.0 / 8.0;
+  let area = (ixs * ixs + iys * iys).sqrt();
+  let nx = ixs / area;
+  let ny = iys / area;
+  let p_l = eos.calc_p(rho_l, u_l, v_l, e_l);
+  let p_r = eos.calc_p(rho_r, u_r, v_r, e_r);
+  let cs_a = 0.5 * (eos.calc_cs(rho_l, u_l, v_l, e_l) + eos.calc_cs(rho_r, u_r, v_r, e_r));
+  let vn_l = nx * u_l + ny * v_l;
+  let vn_r = nx * u_r + ny * v_r;
+  let mach_l = vn_l / cs_a;
+  let mach_r = vn_r / cs_a;
+  let rho_a = 0.5 * (rho_l + rho_r);
+  // scaling function for low-Mach
+  let mach2_bar = 0.5 * (vn_l * vn_l + vn_r * vn_r) / cs_a / cs_a;
+  let mach_o = mach2_bar.max(mach_co * mach_co).min(1.0).sqrt();
+  let fa = mach_o * (2.0 - mach_o);
+  let alpha = 3.0 / 16.0 * (-4.0 + 5.0 * fa * fa);
+  // interface Mach number with pressure diffusion
+  let mach_a = split_mach_plus(&mach_l, &beta) + split_mach_minus(&mach_r, &beta)
+    - kp / fa * (1.0 - sigma * mach2_bar).max(0.0) * (p_r - p_l) / (rho_a * cs_a * cs_a);
+  let mdot = cs_a * (0.5 * (mach_a + mach_a.abs()) * rho_l + 0.5 * (mach_a - mach_a.abs()) * rho_r);
+  // interface pressure with velocity diffusion
+  let pp_l = split_pressure_plus(&mach_l, &alpha);
+  let pm_r = split_pressure_minus(&mach_r, &alpha);
+  let p_half = pp_l * p_l + pm_r * p_r
+    - ku * pp_l * pm_r * 2.0 * rho_a * fa * cs_a * (vn_r - vn_l);
+  assemble_ausm_flux(rho_l, u_l, v_l, e_l, &p_l, rho_r, u_r, v_r, e_r, &p_r,
+    &mdot, &p_half, ixs, iys, vec_fc);
+}
+
+
+
+/// calc convective numerical flux using SLAU or SLAU2 scheme
+#[allow(clippy::too_many_arguments)]
+fn slau<T: EoS>(rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64,
+  rho_r: &f64, u_r: &f64, v_r: &f64, e_r: &f64,
+  ixs: &f64, iys: &f64, is_slau2: &bool, eos: &T, vec_fc: &mut [f64; NE]) {
+  let area = (ixs * ixs + iys * iys).sqrt();
+  let nx = ixs / area;
+  let ny = iys / area;
+  let p_l = eos.calc_p(rho_l, u_l, v_l, e_l);
+  let p_r = eos.calc_p(rho_r, u_r, v_r, e_r);
+  let cs_a = 0.5 * (eos.calc_cs(rho_l, u_l, v_l, e_l) + eos.calc_cs(rho_r, u_r, v_r, e_r));
+  let vn_l = nx * u_l + ny * v_l;
+  let vn_r = nx * u_r + ny * v_r;
+  let mach_l = vn_l / cs_a;
+  let mach_r = vn_r / cs_a;
+  // mass flux with low-Mach pressure diffusion
+  let vn_bar = (rho_l * vn_l.abs() + rho_r * vn_r.abs()) / (rho_l + rho_r);
+  let g = -mach_l.clamp(-1.0, 0.0) * mach_r.clamp(0.0, 1.0);
+  let vn_bar_p = (1.0 - g) * vn_bar + g * vn_l.abs();
+  let vn_bar_m = (1.0 - g) * vn_bar + g * vn_r.abs();
+  let vel_a = (0.5 * (u_l * u_l + v_l * v_l + u_r * u_r + v_r * v_r)).sqrt();
+  let mach_hat = (vel_a / cs_a).min(1.0);
+  let chi = (1.0 - mach_hat) * (1.0 - mach_hat);
+  let mdot = 0.5 * (rho_l * (vn_l + vn_bar_p) + rho_r * (vn_r - vn_bar_m) - chi / cs_a * (p_r - p_l));
+  // pressure flux
+  let beta_p = split_pressure_plus(&mach_l, &0.0);
+  let beta_m = split_pressure_minus(&mach_r, &0.0);
+  let p_half = if *is_slau2 {
+    0.5 * (p_l + p_r) + 0.5 * (beta_p - beta_m) * (p_l - p_r)
+    + vel_a * (beta_p + beta_m - 1.0) * 0.5 * (rho_l + rho_r) * cs_a
+  } else {
+    0.5 * (p_l + p_r) + 0.5 * (beta_p - beta_m) * (p_l - p_r)
+    + (1.0 - chi) * (beta_p + beta_m - 1.0) * 0.5 * (p_l + p_r)
+  };
+  assemble_ausm_flux(rho_l, u_l, v_l, e_l, &p_l, rho_r, u_r, v_r, e_r, &p_r,
+    &mdot, &p_half, ixs, iys, vec_fc);
+}
+
+
+
+/// calc convective numerical flux using Steger-Warming FVS scheme
+/// F = R L+ R^-1 Q_L + R L- R^-1 Q_R,
+/// relying on the homogeneity of the flux, valid for ideal gas
+#[allow(clippy::too_many_arguments)]
+fn steger_warming<T: EoS>(rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64,
+  rho_r: &f64, u_r: &f64, v_r: &f64, e_r: &f64,
+  ixs: &f64, iys: &f64, s: &f64, eos: &T, vec_fc: &mut [f64; NE]) {
+  let ix = ixs / s;
+  let iy = iys / s;
+  let mut dia_lam = [0.0; NE];
+  let mut mat_r = [[0.0; NE]; NE];
+  let mut mat_rinv = [[0.0; NE]; NE];
+  let mut vec_q = [0.0; NE];
+  *vec_fc = [0.0; NE];
+  // positive part from the left state
+  eos.calc_eigen(rho_l, u_l, v_l, e_l, &ix, &iy, &mut dia_lam, &mut mat_r, &mut mat_rinv);
+  super::conserved::calc_conservative(rho_l, u_l, v_l, e_l, s, &mut vec_q);
+  for i in 0..NE {
+    for j in 0..NE {
+      for k in 0..NE {
+        vec_fc[i] += mat_r[i][j] * dia_lam[j].max(0.0) * mat_rinv[j][k] * vec_q[k];
+      }
+    }
+  }
+  // negative part from the right state
+  eos.calc_eigen(rho_r, u_r, v_r, e_r, &ix, &iy, &mut dia_lam, &mut mat_r, &mut mat_rinv);
+  super::conserved::calc_conservative(rho_r, u_r, v_r, e_r, s, &mut vec_q);
+  for i in 0..NE {
+    for j in 0..NE {
+      for k in 0..NE {
+        vec_fc[i] += mat_r[i][j] * dia_lam[j].min(0.0) * mat_rinv[j][k] * vec_q[k];
+      }
+    }
+  }
+}
+
+
+
+/// split flux of Van Leer FVS, per unit area of the cell-boundary
+/// positive part for sign = 1, negative part for sign = -1
+#[inline]
+#[allow(clippy::too_many_arguments)]
+fn van_leer_split<T: EoS>(rho: &f64, u: &f64, v: &f64, e: &f64,
+  nx: &f64, ny: &f64, sign: &f64, eos: &T, vec_fc: &mut [f64; NE]) {
+  let cs = eos.calc_cs(rho, u, v, e);
+  let vn = nx * u + ny * v;
+  let mach = vn / cs;
+  if mach.abs() >= 1.0 {
+    if mach * sign > 0.0 {
+      super::conserved::calc_flux_conv::<T>(rho, u, v, e, nx, ny, eos, vec_fc);
+    } else {
+      *vec_fc = [0.0; NE];
+    }
+    return;
+  }
+  // effective ratio of specific heats
+  let p = eos.calc_p(rho, u, v, e);
+  let gamma = rho * cs * cs / p;
+  let f_mass = sign * 0.25 * rho * cs * (mach + sign) * (mach + sign);
+  let vn_c = (gamma - 1.0) * vn + sign * 2.0 * cs;
+  vec_fc[0] = f_mass;
+  vec_fc[1] = f_mass * (u + nx * (-vn + sign * 2.0 * cs) / gamma);
+  vec_fc[2] = f_mass * (v + ny * (-vn + sign * 2.0 * cs) / gamma);
+  vec_fc[3] = f_mass * (0.5 * vn_c * vn_c / (gamma * gamma - 1.0) + 0.5 * (u * u + v * v - vn * vn));
+}
+
+
+
+/// calc convective numerical flux using Van Leer FVS scheme
+#[allow(clippy::too_many_arguments)]
+fn van_leer<T: EoS>(rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64,
+  rho_r: &f64, u_r: &f64, v_r: &f64, e_r: &f64,
+  ixs: &f64, iys: &f64, eos: &T, vec_fc: &mut [f64; NE]) {
+  let area = (ixs * ixs + iys * iys).sqrt();
+  let nx = ixs / area;
+  let ny = iys / area;
+  let mut vec_f_p = [0.0; NE];
+  van_leer_split(rho_l, u_l, v_l, e_l, &nx, &ny, &1.0, eos, &mut vec_f_p);
+  let mut vec_f_m = [0.0; NE];
+  van_leer_split(rho_r, u_r, v_r, e_r, &nx, &ny, &-1.0, eos, &mut vec_f_m);
+  for i in 0..NE {
+    vec_fc[i] = area * (vec_f_p[i] + vec_f_m[i]);
+  }
+}
+
+
+
+/// calc convective numerical flux using Lax-Friedrichs scheme
+/// local (Rusanov) one if sp_glob is None,
+/// global one with the domain maximum of |u|+c otherwise
+#[allow(clippy::too_many_arguments)]
+pub fn lax_friedrichs<T: EoS>(rho_l: &f64, u_l: &f64, v_l: &f64, e_l: &f64,
+  rho_r: &f64, u_r: &f64, v_r: &f64, e_r: &f64,
+  ixs: &f64, iys: &f64, s: &f64, sp_glob: &Option<f64>, eos: &T, vec_fc: &mut [f64; NE]) {
+  let ix = ixs / s;
+  let iy = iys / s;
+  let sqr = (ix * ix + iy * iy).sqrt();
+  let sp = match sp_glob {
+    Some(sp_glob) => sp_glob * sqr,
+    None => {
+      let sp_l = (ix * u_l + iy * v_l).abs() + eos.calc_cs(rho_l, u_l, v_l, e_l) * sqr;
+      let sp_r = (ix * u_r + iy * v_r).abs() + eos.calc_cs(rho_r, u_r, v_r, e_r) * sqr;
+      sp_l.max(sp_r)
+    },
+  };
+  let mut vec_q_l = [0.0; NE];
+  super::conserved::calc_conservative(rho_l, u_l, v_l, e_l, s, &mut vec_q_l);
+  let mut vec_q_r = [0.0; NE];
+  super::conserved::calc_conservative(rho_r, u_r, v_r, e_r, s, &mut vec_q_r);
+  let mut vec_f_l = [0.0; NE];
+  super::conserved::calc_flux_conv::<T>(rho_l, u_l, v_l, e_l, ixs, iys, eos, &mut vec_f_l);
+  let mut vec_f_r = [0.0; NE];
+  super::conserved::calc_flux_conv::<T>(rho_r, u_r, v_r, e_r, ixs, iys, eos, &mut vec_f_r);
+  for i in 0..NE {
+    vec_fc[i] = 0.5 * (vec_f_l[i] + vec_f_r[i] - sp * (vec_q_r[i] - vec_q_l[i]));
+  }
+}
+
+
+
+#[test]
+fn test_consistency() {
+  // every flux scheme should return the physical flux for a uniform state
+  let eos = super::super::super::super::IdealEoS::new(&1.4, &1.0);
+  let (rho, u, v, e) = (1.2, 0.3, -0.4, 2.5);
+  let (ixs, iys, s) = (0.8, 0.3, 0.5);
+  let mut vec_f = [0.0; NE];
+  super::conserved::calc_flux_conv(&rho, &u, &v, &e, &ixs, &iys, &eos, &mut vec_f);
+  for scheme in ["Roe_FDS", "Roe_FDS_Harten", "Roe_FDS_Harten_Hyman", "Roe_FDS_Thornber", "Rotated_RHLL",
+    "HLL", "HLLC", "AUSM+", "AUSM+-up", "SLAU", "SLAU2",
+    "Steger_Warming", "Van_Leer", "Rusanov"] {
+    let mut vec_fc = [0.0; NE];
+    calc_num_flux(scheme, &rho, &u, &v, &e, &rho, &u, &v, &e, &ixs, &iys, &s, &eos, &mut vec_fc);
+    for k in 0..NE {
+      assert!((vec_fc[k] - vec_f[k]).abs() < 1.0e-12, "{} is not consistent", scheme);
+    }
+  }
+  let mut vec_fc = [0.0; NE];
+  lax_friedrichs(&rho, &u, &v, &e, &rho, &u, &v, &e, &ixs, &iys, &s, &Some(2.0), &eos, &mut vec_fc);
+  for k in 0..NE {
+    assert!((vec_fc[k] - vec_f[k]).abs() < 1.0e-12, "Lax_Friedrichs is not consistent");
+  }
+  // HLLC should resolve a stationary contact exactly
+  let p = 1.0;
+  let e_l = p / 0.4;
+  let e_r = p / 0.4;
+  let mut vec_fc = [0.0; NE];
+  calc_num_flux("HLLC", &1.0, &0.0, &0.0, &e_l, &0.125, &0.0, &0.0, &e_r, &ixs, &iys, &s, &eos, &mut vec_fc);
+  assert!(vec_fc[0].abs() < 1.0e-12);
+  assert!((vec_fc[1] - ixs * p).abs() < 1.0e-12);
+  assert!((vec_fc[2] - iys * p).abs() < 1.0e-12);
+  assert!(vec_fc[3].abs() < 1.0e-12);
+}
+
+
+
+#[test]
+fn test_low_mach() {
+  // dissipation of the low-Mach corrected Roe flux should scale with Mach number
+  // for a velocity jump at Ma = 0.01
+  let eos = super::super::super::super::IdealEoS::new(&1.4, &1.0);
+  let mach = 0.01;
+  let p = 1.0 / 1.4 / mach / mach;
+  let (u_l, u_r) = (1.0, 0.9);
+  let e_l = p / 0.4 + 0.5 * u_l * u_l;
+  let e_r = p / 0.4 + 0.5 * u_r * u_r;
+  let mut vec_f_l = [0.0; NE];
+  super::conserved::calc_flux_conv(&1.0, &u_l, &0.0, &e_l, &1.0, &0.0, &eos, &mut vec_f_l);
+  let mut vec_f_r = [0.0; NE];
+  super::conserved::calc_flux_conv(&1.0, &u_r, &0.0, &e_r, &1.0, &0.0, &eos, &mut vec_f_r);
+  let mut dissip = [0.0; 2];
+  for (n, scheme) in ["Roe_FDS", "Roe_FDS_Thornber"].iter().enumerate() {
+    let mut vec_fc = [0.0; NE];
+    calc_num_flux(scheme, &1.0, &u_l, &0.0, &e_l, &1.0, &u_r, &0.0, &e_r, &1.0, &0.0, &1.0, &eos, &mut vec_fc);
+    dissip[n] = (vec_fc[1] - 0.5 * (vec_f_l[1] + vec_f_r[1])).abs();
+  }
+  assert!(dissip[1] < 2.0 * mach * dissip[0]);
+}
+
+
+
+#[test]
+fn test_roe_property() {
+  // Roe matrix with the projected average should satisfy A (q_r - q_l) = f_r - f_l for real gases
+  let eos = super::super::super::super::CubicEoS::new("Peng_Robinson", &1.29, &1.0, &1.0, &1.0, &0.225);
+  let (rho_l, u_l, v_l) = (3.0, 0.2, 0.1);
+  let (rho_r, u_r, v_r) = (1.5, -0.1, 0.3);
+  let e_l = eos.calc_e_wp(&rho_l, &u_l, &v_l, &1.5);
+  let e_r = eos.calc_e_wp(&rho_r, &u_r, &v_r, &0.9);
+  let (ix, iy) = (0.6, 0.8);
+  let mut u_a = 0.0;
+  let mut v_a = 0.0;
+  let mut h_a = 0.0;
+  let mut cs_a = 0.0;
+  let mut chi_a = 0.0;
+  let mut gr_a = 0.0;
+  roe_average(&rho_l, &u_l, &v_l, &e_l, &rho_r, &u_r, &v_r, &e_r, &eos,
+    &mut u_a, &mut v_a, &mut h_a, &mut cs_a, &mut chi_a, &mut gr_a);
+  let mut dia_lam = [0.0; NE];
+  let mut mat_r = [[0.0; NE]; NE];
+  let mut mat_rinv = [[0.0; NE]; NE];
+  calc_eigen_general(&u_a, &v_a, &cs_a, &h_a, &chi_a, &gr_a, &ix, &iy, &mut dia_lam, &mut mat_r, &mut mat_rinv);
+  let mut vec_q_l = [0.0; NE];
+  super::conserved::calc_conservative(&rho_l, &u_l, &v_l, &e_l, &1.0, &mut vec_q_l);
+  let mut vec_q_r = [0.0; NE];
+  super::conserved::calc_conservative(&rho_r, &u_r, &v_r, &e_r, &1.0, &mut vec_q_r);
+  let mut vec_f_l = [0.0; NE];
+  super::conserved::calc_flux_conv(&rho_l, &u_l, &v_l, &e_l, &ix, &iy, &eos, &mut vec_f_l);
+  let mut vec_f_r = [0.0; NE];
+  super::conserved::calc_flux_conv(&rho_r, &u_r, &v_r, &e_r, &ix, &iy, &eos, &mut vec_f_r);
+  for i in 0..NE {
+    let mut adq = 0.0;
+    for j in 0..NE {
+      for k in 0..NE {
+        adq += mat_r[i][j] * dia_lam[j] * mat_rinv[j][k] * (vec_q_r[k] - vec_q_l[k]);
+      }
+    }
+    assert!((adq - (vec_f_r[i] - vec_f_l[i])).abs() < 1.0e-10);
+  }
+}
+
+
+
+#[test]
+fn test_entropy_fix() {
+  // the fixed eigen value is continuous at |lam| = delta, positive at lam = 0 and unchanged outside
+  let delta = 0.2;
+  assert!((abs_entropy_fixed(&delta, &delta) - delta).abs() < 1.0e-15);
+  assert!((abs_entropy_fixed(&0.0, &delta) - 0.5 * delta).abs() < 1.0e-15);
+  assert!(abs_entropy_fixed(&-0.1, &delta) > 0.1);
+  assert_eq!(abs_entropy_fixed(&-0.3, &delta), 0.3);
+  // stationary expansion shock: the post-shock state of a Mach 2 normal shock on the left
+  // and the pre-shock state on the right satisfy the Rankine-Hugoniot relation,
+  // so that Roe flux without the fix keeps it as a steady solution, while the fix breaks it
+  let gamma = 1.4;
+  let eos = super::super::super::super::IdealEoS::new(&gamma, &1.0);
+  let mach = 2.0;
+  let (rho_r, p_r) = (1.0, 1.0);
+  let u_r = mach * (gamma * p_r / rho_r).sqrt();
+  let rho_l = rho_r * (gamma + 1.0) * mach * mach / ((gamma - 1.0) * mach * mach + 2.0);
+  let p_l = p_r * (1.0 + 2.0 * gamma / (gamma + 1.0) * (mach * mach - 1.0));
+  let u_l = u_r * rho_r / rho_l;
+  let e_l = eos.calc_e_wp(&rho_l, &u_l, &0.0, &p_l);
+  let e_r = eos.calc_e_wp(&rho_r, &u_r, &0.0, &p_r);
+  // transonic in the u - c wave
+  assert!(u_l < (gamma * p_l / rho_l).sqrt() && u_r > (gamma * p_r / rho_r).sqrt());
+  let mut vec_f_l = [0.0; NE];
+  super::conserved::calc_flux_conv(&rho_l, &u_l, &0.0, &e_l, &1.0, &0.0, &eos, &mut vec_f_l);
+  for (scheme, steady) in [("Roe_FDS", true), ("Roe_FDS_Harten", false), ("Roe_FDS_Harten_Hyman", false)] {
+    let mut vec_fc = [0.0; NE];
+    calc_num_flux(scheme, &rho_l, &u_l, &0.0, &e_l, &rho_r, &u_r, &0.0, &e_r, &1.0, &0.0, &1.0, &eos, &mut vec_fc);
+    let diff = (vec_fc[0] - vec_f_l[0]).abs() / vec_f_l[0];
+    if steady {
+      assert!(diff < 1.0e-12, "{} breaks the expansion shock", scheme);
+    } else {
+      assert!(diff > 1.0e-3, "{} keeps the expansion shock", scheme);
+    }
+  }
+}
@@ -55,10 +55,11 @@ pub fn mp5_sub(q2m: &f64, qm: &f64, q: &f64, qp: &f64, q2p: &f64,
     let q_lc = q + 0.5 * (q - qm) + 4.0 / 3.0 * d_mm;
     let qmin = q.min(*qp).min(q_md).max(q.min(q_ul).min(q_lc));
     let qmax = q.max(*qp).max(q_md).min(q.max(q_ul).max(q_lc));
-    *q_l = median(&q_l, &qmin, &qmax);
+    *q_l = median(q_l, &qmin, &qmax);
   }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn mp5(q2m: &f64, qm: &f64, q: &f64, qp: &f64, q2p: &f64, q3p: &f64,
   q_l: &mut f64, q_r: &mut f64) {
     mp5_sub(q2m, qm, q, qp, q2p, q_l);
@@ -77,6 +78,12 @@ pub fn central_diff4(q0: &f64, q1: &f64, q3: &f64, q4: &f64) -> f64 {
   q0 / 12.0 - 2.0 / 3.0 * q1 + 2.0 / 3.0 * q3 - q4 / 12.0
 } 
 
+/// 3rd difference for artificial dissipation
+/// returning 3rd difference at i=1.5 from values at i=0,1,2,3
+pub fn third_diff(q0: &f64, q1: &f64, q2: &f64, q3: &f64) -> f64 {
+  q3 - 3.0 * q2 + 3.0 * q1 - q0
+}
+
 #[test]
 fn test() {
   println!("{}", minmod4(&1.0,&2.0,&3.0,&2.0));
@@ -0,0 +1,159 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use super::super::super::{eos::EoS, EulerEq, GenStructCoord, HybridScheme, IdealGas, NF};
+
+
+
+impl HybridScheme {
+  /// whether the central flux is used at any face
+  #[inline]
+  pub fn is_active(&self) -> bool {
+    self.central != "none"
+  }
+
+  /// whether the central flux is used at the face between the cells with the sensors sensor_l and sensor_r
+  #[inline]
+  pub fn is_central(&self, sensor_l: &f64, sensor_r: &f64) -> bool {
+    self.is_active() && (self.sensor == "none" || sensor_l.max(*sensor_r) < self.threshold)
+  }
+
+  /// blend the central flux vec_fc into the upwind flux vec_f by the ratio of dissipation
+  #[inline]
+  pub fn blend(&self, vec_fc: &[f64; NF], vec_f: &mut [f64; NF]) {
+    for k in 0..NF {
+      vec_f[k] = self.dissipation * vec_f[k] + (1.0 - self.dissipation) * vec_fc[k];
+    }
+  }
+
+  /// description of hybrid scheme for settings file
+  pub fn describe(&self) -> String {
+    if self.is_active() {
+      format!("hybrid central/upwind scheme.
+    central flux = {}
+    shock sensor = {}
+    threshold = {}
+    dissipation = {}", self.central, self.sensor, self.threshold, self.dissipation)
+    } else {
+      "hybrid central/upwind scheme is not used.".to_string()
+    }
+  }
+}
+
+
+
+impl<const NI: usize, const NJ: usize, const NB: usize> EulerEq::<NI,NJ,NB> {
+  /// calc shock sensor in the cells [NB-1:NI-NB+1][NB-1:NJ-NB+1] from basic variables and pressure in arr_p
+  /// "Ducros": (div u)^2 / ((div u)^2 + |rot u|^2 + eps), close to 1 at shocks and 0 in vortical regions
+  /// "Jameson": normalized second difference of pressure, maximum of i- and j-directions
+  /// "none": 0 everywhere
+  pub fn calc_sensor(&mut self, sensor: &str, u: &[[f64; NJ]; NI], v: &[[f64; NJ]; NI], coord: &GenStructCoord::<NI,NJ,NB>) {
+    for i in (NB-1)..(NI-NB+1) {
+      for j in (NB-1)..(NJ-NB+1) {
+        self.arr_sensor[i][j] = match sensor {
+          "Ducros" => {
+            let (im, jm) = (i + 1 - NB, j + 1 - NB);
+            let s_a = 0.25 * (coord.s[i-1][j-1] + coord.s[i][j-1] + coord.s[i-1][j] + coord.s[i][j]);
+            let u_i = 0.5 * (u[i+1][j] - u[i-1][j]);
+            let u_j = 0.5 * (u[i][j+1] - u[i][j-1]);
+            let v_i = 0.5 * (v[i+1][j] - v[i-1][j]);
+            let v_j = 0.5 * (v[i][j+1] - v[i][j-1]);
+            let div = (coord.ixs[im][jm] * u_i + coord.jxs[im][jm] * u_j + coord.iys[im][jm] * v_i + coord.jys[im][jm] * v_j) / s_a;
+            let rot = (coord.ixs[im][jm] * v_i + coord.jxs[im][jm] * v_j - coord.iys[im][jm] * u_i - coord.jys[im][jm] * u_j) / s_a;
+            div * div / (div * div + rot * rot + 1.0e-10)
+          },
+          "Jameson" => {
+            let p = &self.arr_p;
+            let psi_i = (p[i+1][j] - 2.0 * p[i][j] + p[i-1][j]).abs() / (p[i+1][j] + 2.0 * p[i][j] + p[i-1][j]);
+            let psi_j = (p[i][j+1] - 2.0 * p[i][j] + p[i][j-1]).abs() / (p[i][j+1] + 2.0 * p[i][j] + p[i][j-1]);
+            psi_i.max(psi_j)
+          },
+          "none" => 0.0,
+          _ => panic!("Shock sensor not specified."),
+        };
+      }
+    }
+  }
+
+
+  /// write shock sensor into f_name, one value per line in the same order as basic variables
+  pub fn output_sensor(&self, f_name: &str) {
+    let mut file = BufWriter::new(File::create(f_name).unwrap());
+    for i in 0..NI {
+      for j in 0..NJ {
+        writeln!(file, "{:.18e}", self.arr_sensor[i][j]).unwrap();
+      }
+    }
+  }
+}
+
+
+
+impl<const NI: usize, const NJ: usize, const NB: usize, T: EoS> IdealGas::<NI,NJ,NB,T> {
+  /// write shock sensor of hybrid scheme into f_name,
+  /// evaluated from the current basic variables (not from the last stage of the step)
+  pub fn output_sensor(&mut self, f_name: &str) {
+    for i in 0..NI {
+      for j in 0..NJ {
+        self.eq.arr_p[i][j] = self.eos.frozen(&self.basic.phi[i][j]).calc_p(&self.basic.rho[i][j], &self.basic.u[i][j], &self.basic.v[i][j], &self.basic.e[i][j]);
+      }
+    }
+    self.eq.calc_sensor(self.hybrid.sensor, &self.basic.u, &self.basic.v, &self.coord);
+    self.eq.output_sensor(f_name);
+  }
+}
+
+
+
+#[test]
+fn test_shock_sensor() {
+  use super::super::super::IdealEoS;
+  // the cells at a shock use the upwind flux at all their faces (rhs equal to that of the upwind scheme),
+  // and the sensor stays below the threshold everywhere in a smooth vortex, so that the central flux is used
+  const NI: usize = 24;
+  const NJ: usize = 24;
+  const NB: usize = 4;
+  let dx = 1.0 / (NI - 2 * NB) as f64;
+  let xy = |i: usize, j: usize| ((i as f64 - NB as f64 + 0.5) * dx, (j as f64 - NB as f64 + 0.5) * dx);
+  let threshold = 0.1;
+  for sensor in ["Ducros", "Jameson"] {
+    let new_fluid = |hybrid: bool, state: &dyn Fn(f64, f64) -> (f64, f64, f64, f64)| {
+      let mut fluid = IdealGas::<NI,NJ,NB,_>::new_for_test(IdealEoS::new(&1.4, &1.0), xy, &false);
+      if hybrid {
+        fluid.hybrid = HybridScheme::new("Kennedy_Gruber", sensor, &threshold, &0.0);
+      }
+      fluid.set_state(|i, j| state(xy(i, j).0, xy(i, j).1));
+      let arr_rhs = *fluid.calc_rhs_for_test("MUSCL_minmod_basic", "Roe_FDS");
+      (fluid.eq.arr_sensor, arr_rhs)
+    };
+    // stationary normal shock of Mach 2 at x = 0.5 (density and pressure ratios 8/3 and 4.5)
+    let shock = |x: f64, _y: f64| if x < 0.5 {
+      (1.0, 2.0 * 1.4_f64.sqrt(), 0.0, 1.0)
+    } else {
+      (8.0 / 3.0, 0.75 * 1.4_f64.sqrt(), 0.0, 4.5)
+    };
+    let (arr_sensor, arr_rhs) = new_fluid(true, &shock);
+    let (_, arr_rhs_upwind) = new_fluid(false, &shock);
+    // the cells on both sides of the shock
+    for i in [NB + 7, NB + 8] {
+      for j in NB..(NJ-NB) {
+        assert!(arr_sensor[i][j] >= threshold, "{} {} {} {}", sensor, i, j, arr_sensor[i][j]);
+        assert_eq!(arr_rhs[i-NB][j-NB], arr_rhs_upwind[i-NB][j-NB]);
+      }
+    }
+    // smooth vortex at (0.5, 0.5)
+    let vortex = |x: f64, y: f64| {
+      let (dx, dy) = (x - 0.5, y - 0.5);
+      let f = (1.0 - (dx * dx + dy * dy) / 0.04).exp();
+      let temp = 1.0 - 0.4 / 1.4 * 0.005 * f * f;
+      (temp.powf(2.5), -0.1 * dy * f, 0.1 * dx * f, temp.powf(3.5))
+    };
+    let (arr_sensor, arr_rhs) = new_fluid(true, &vortex);
+    let (_, arr_rhs_upwind) = new_fluid(false, &vortex);
+    for (i, arr_sensor_i) in arr_sensor.iter().enumerate().take(NI-NB+1).skip(NB-1) {
+      for (j, sensor_ij) in arr_sensor_i.iter().enumerate().take(NJ-NB+1).skip(NB-1) {
+        assert!(*sensor_ij < threshold, "{} {} {} {}", sensor, i, j, sensor_ij);
+      }
+    }
+    assert_ne!(arr_rhs[6][6], arr_rhs_upwind[6][6]);
+  }
+}
@@ -0,0 +1,51 @@
+use super::super::super::{NE, NF, NS};
+use super::fnd;
+
+
+
+/// construct conservative var of passive scalars from basic var
+#[inline]
+pub fn calc_conservative(rho: &f64, phi: &[f64; NS], s: &f64, vec_q: &mut [f64; NF]) {
+  for (q, p) in vec_q[NE..].iter_mut().zip(phi) {
+    *q = rho * p * s;
+  }
+}
+
+
+
+/// construct passive scalars from conservative var
+#[inline]
+pub fn calc_basic(vec_q: &[f64; NF], phi: &mut [f64; NS]) {
+  for (p, q) in phi.iter_mut().zip(&vec_q[NE..]) {
+    *p = q / vec_q[0];
+  }
+}
+
+
+
+/// construct flux of passive scalars from mass flux in vec_fc[0]
+/// with passive scalars upwinded by the direction of mass flux
+#[inline]
+pub fn calc_flux(phi_l: &[f64; NS], phi_r: &[f64; NS], vec_fc: &mut [f64; NF]) {
+  let phi = if vec_fc[0] >= 0.0 { phi_l } else { phi_r };
+  let mass_flux = vec_fc[0];
+  for (f, p) in vec_fc[NE..].iter_mut().zip(phi) {
+    *f = mass_flux * p;
+  }
+}
+
+
+
+/// reconstruction of passive scalars by the same method as basic var
+/// from values at i-3, ..., i+2 to the left/right of i-0.5
+#[inline]
+pub fn reconst(reconstruction: &str, phi: [&[f64; NS]; 6],
+  phi_l: &mut [f64; NS], phi_r: &mut [f64; NS]) {
+  for (n, (q_l, q_r)) in phi_l.iter_mut().zip(phi_r.iter_mut()).enumerate() {
+    match reconstruction {
+      "MUSCL_minmod_basic" => fnd::muscl_minmod(&phi[1][n], &phi[2][n], &phi[3][n], &phi[4][n], q_l, q_r),
+      "MP5_basic" => fnd::mp5(&phi[0][n], &phi[1][n], &phi[2][n], &phi[3][n], &phi[4][n], &phi[5][n], q_l, q_r),
+      _ => panic!("Reconstruction method not specified."),
+    }
+  }
+}
@@ -0,0 +1,186 @@
+use super::super::super::{eos::EoS, PositivityLimiter};
+
+
+
+impl PositivityLimiter {
+  /// numbers of cells with limited face states and clipped cells since the last call
+  pub fn take_counts(&mut self) -> (usize, usize) {
+    let counts = (self.n_face, self.n_clip);
+    self.n_face = 0;
+    self.n_clip = 0;
+    counts
+  }
+
+  /// description of limiter for settings file
+  pub fn describe(&self) -> String {
+    format!("positivity limiter.
+    face = {}
+    rho_min = {}
+    p_min = {}
+    clip = {}
+    rho_clip = {}
+    p_clip = {}", self.face, self.rho_min, self.p_min, self.clip, self.rho_clip, self.p_clip)
+  }
+
+  /// ratio of blending the face state reconstructed in the cell of (rho_c, u_c, v_c, e_c)
+  /// toward the cell value in conservative variables (Zhang-Shu), 1 if admissible,
+  /// so that density and then pressure of the blended state are above the floors
+  /// e is pressure if pressure (for mixture or well-balanced body force) and total energy per volume otherwise
+  /// any smaller ratio is also admissible if pressure is concave in conservative variables (e.g. ideal gas),
+  /// so the minimum over the faces of the cell can be applied to all of them
+  #[inline]
+  #[allow(clippy::too_many_arguments)]
+  pub fn calc_theta<T: EoS>(&self, eos: &T, pressure: &bool,
+    rho_c: &f64, u_c: &f64, v_c: &f64, e_c: &f64,
+    rho_f: &f64, u_f: &f64, v_f: &f64, e_f: &f64) -> f64 {
+    let calc_p = |q: &[f64; 4]| if *pressure {
+      q[3]
+    } else {
+      eos.calc_p(&q[0], &(q[1] / q[0]), &(q[2] / q[0]), &q[3])
+    };
+    // blending ratio of the face state, 0 for the cell value
+    let ratio = |x_c: f64, x_f: f64, x_min: f64| {
+      let theta = (x_c - x_min) / (x_c - x_f);
+      if theta.is_nan() { 0.0 } else { theta.clamp(0.0, 1.0) }
+    };
+    let q_c = [*rho_c, rho_c * u_c, rho_c * v_c, *e_c];
+    let mut q_f = [*rho_f, rho_f * u_f, rho_f * v_f, *e_f];
+    let mut theta = 1.0;
+    if q_f[0].is_nan() || q_f[0] < self.rho_min {
+      theta = ratio(q_c[0], q_f[0], self.rho_min);
+      for k in 0..4 {
+        q_f[k] = q_c[k] + theta * (q_f[k] - q_c[k]);
+      }
+    }
+    let p_f = calc_p(&q_f);
+    if p_f.is_nan() || p_f < self.p_min {
+      theta *= ratio(calc_p(&q_c), p_f, self.p_min);
+    }
+    theta
+  }
+
+  /// blend the face state reconstructed in the cell of (rho_c, u_c, v_c, e_c) toward the cell value
+  /// in conservative variables by the ratio theta of the cell
+  #[inline]
+  #[allow(clippy::too_many_arguments)]
+  pub fn blend(theta: &f64, rho_c: &f64, u_c: &f64, v_c: &f64, e_c: &f64,
+    rho_f: &mut f64, u_f: &mut f64, v_f: &mut f64, e_f: &mut f64) {
+    if *theta == 1.0 {
+      return;
+    }
+    let q_c = [*rho_c, rho_c * u_c, rho_c * v_c, *e_c];
+    let q_f = [*rho_f, *rho_f * *u_f, *rho_f * *v_f, *e_f];
+    let q = [0, 1, 2, 3].map(|k| q_c[k] + theta * (q_f[k] - q_c[k]));
+    *rho_f = q[0];
+    *u_f = q[1] / q[0];
+    *v_f = q[2] / q[0];
+    *e_f = q[3];
+  }
+
+  /// count the cells with limited face states by the ratios arr_theta
+  pub fn count_face<const NI: usize, const NJ: usize>(&mut self, arr_theta: &[[f64; NJ]; NI]) {
+    self.n_face += arr_theta.iter().flatten().filter(|theta| **theta < 1.0).count();
+  }
+
+  /// clip density and pressure of the cell to the floors of clipping, keeping velocity
+  /// velocity is set to 0 if not finite
+  /// returns whether clipped
+  #[inline]
+  pub fn clip<T: EoS>(&mut self, eos: &T, rho: &mut f64, u: &mut f64, v: &mut f64, e: &mut f64) -> bool {
+    let mut clipped = false;
+    if rho.is_nan() || *rho < self.rho_clip {
+      *rho = self.rho_clip;
+      clipped = true;
+    }
+    if !(u.is_finite() && v.is_finite()) {
+      *u = 0.0;
+      *v = 0.0;
+      clipped = true;
+    }
+    let p = eos.calc_p(rho, u, v, e);
+    if p.is_nan() || p < self.p_clip {
+      *e = eos.calc_e_wp(rho, u, v, &self.p_clip);
+      clipped = true;
+    }
+    if clipped {
+      self.n_clip += 1;
+    }
+    clipped
+  }
+}
+
+
+
+#[test]
+fn test_positivity_limiter() {
+  let eos = super::super::super::IdealEoS::new(&1.4, &1.0);
+  let mut limiter = PositivityLimiter::new(&true, &1.0e-3, &1.0e-3, &true, &1.0e-6, &1.0e-6);
+  // overshooting face states with negative density and negative pressure
+  let (rho_c, u_c, v_c, e_c) = (1.0, 2.0, 0.0, 4.5);
+  let faces = [(-0.2, 2.0, 0.0, 4.5), (1.0, 3.0, 0.0, 4.0), (0.9, 1.8, 0.1, 4.0)];
+  let thetas = faces.map(|(rho, u, v, e)| limiter.calc_theta(&eos, &false, &rho_c, &u_c, &v_c, &e_c, &rho, &u, &v, &e));
+  // admissible face state is not limited
+  assert_eq!(thetas[2], 1.0);
+  // the minimum ratio over the faces keeps all of them admissible
+  let theta = thetas.iter().fold(1.0_f64, |a, b| a.min(*b));
+  assert!(theta < 1.0);
+  for (rho, u, v, e) in faces {
+    let (mut rho_f, mut u_f, mut v_f, mut e_f) = (rho, u, v, e);
+    PositivityLimiter::blend(&theta, &rho_c, &u_c, &v_c, &e_c, &mut rho_f, &mut u_f, &mut v_f, &mut e_f);
+    assert!(rho_f >= 1.0e-3 * (1.0 - 1.0e-12));
+    assert!(eos.calc_p(&rho_f, &u_f, &v_f, &e_f) >= 1.0e-3 * (1.0 - 1.0e-12));
+  }
+  let mut arr_theta = [[1.0; 3]; 2];
+  arr_theta[1][2] = theta;
+  limiter.count_face(&arr_theta);
+  // clipped to its own floors
+  let (mut rho, mut u, mut v, mut e) = (-1.0e-5, f64::NAN, 0.0, 1.0);
+  assert!(limiter.clip(&eos, &mut rho, &mut u, &mut v, &mut e));
+  assert_eq!(rho, 1.0e-6);
+  assert_eq!(limiter.take_counts(), (1, 1));
+  assert_eq!(limiter.take_counts(), (0, 0));
+}
+
+
+
+#[test]
+fn test_positivity_vacuum() {
+  use super::super::Marching;
+  use super::super::super::{IdealEoS, IdealGas};
+  // double rarefaction of ideal gas with u = -3 and 3 diverging at x = 0.5, leaving near vacuum
+  // (vacuum if |u| > 2 c / (gamma - 1) = 3.74), where MP5 overshoots to negative pressure
+  // at the faces unless limited, with the Rusanov flux and the CFL number 0.4 (<= 0.5 in 1D)
+  const NI: usize = 108;
+  const NJ: usize = 10;
+  const NB: usize = 4;
+  let dx = 1.0 / (NI - 2 * NB) as f64;
+  let xy = |i: usize, j: usize| ((i as f64 - NB as f64 + 0.5) * dx, j as f64 * 1.0e4);
+  let (u_0, p_0) = (3.0, 0.4_f64);
+  let dt = 0.4 * dx / (u_0 + (1.4 * p_0).sqrt());
+  for face in [false, true] {
+    let mut fluid = IdealGas::<NI,NJ,NB,_>::new_for_test(IdealEoS::new(&1.4, &1.0), xy, &false);
+    fluid.limiter = PositivityLimiter::new(&face, &1.0e-10, &1.0e-10, &false, &1.0e-10, &1.0e-10);
+    fluid.set_state(|i, _| (1.0, if xy(i, 0).0 < 0.5 { -u_0 } else { u_0 }, 0.0, p_0));
+    for _ in 0..100 {
+      fluid.march_rk(&dt, "SSPRK3", "periodical_in_i", "MP5_basic", "Rusanov");
+      if fluid.blow_up.is_some() {
+        break;
+      }
+    }
+    if !face {
+      let (_, i, _) = fluid.blow_up.take().expect("positivity should fail without the limiter");
+      assert!((xy(NB + i, 0).0 - 0.5).abs() < 0.1);
+      continue;
+    }
+    assert!(fluid.blow_up.is_none());
+    let (n_face, _) = fluid.limiter.take_counts();
+    assert!(n_face > 0);
+    let basic = &fluid.basic;
+    for i in NB..(NI-NB) {
+      let p = fluid.eos.calc_p(&basic.rho[i][NJ/2], &basic.u[i][NJ/2], &basic.v[i][NJ/2], &basic.e[i][NJ/2]);
+      assert!(basic.rho[i][NJ/2] > 0.0 && p > 0.0);
+    }
+    // near vacuum at the center
+    assert!(basic.rho[NI/2][NJ/2] < 0.1);
+  }
+}
@@ -0,0 +1,218 @@
+use super::super::super::{eos::EoS, transport::Transport, GenStructCoord, TransportProp, NF, NS};
+use super::fnd;
+
+
+
+/// construct viscous flux from velocity/temperature gradients
+/// v_r is v/r in axisymmetric flow, and 0 otherwise
+#[inline]
+#[allow(clippy::too_many_arguments)]
+fn calc_flux_visc(u: &f64, v: &f64, ux: &f64, uy: &f64, vx: &f64, vy: &f64, v_r: &f64,
+  tx: &f64, ty: &f64, mu: &f64, kappa: &f64, ixs: &f64, iys: &f64, vec_fv: &mut [f64; NF]) {
+    // viscous stress with Stokes hypothesis
+    let div = ux + vy + v_r;
+    let txx = mu * (2.0 * ux - 2.0 / 3.0 * div);
+    let tyy = mu * (2.0 * vy - 2.0 / 3.0 * div);
+    let txy = mu * (uy + vx);
+    // Fourier heat flux
+    let qx = -kappa * tx;
+    let qy = -kappa * ty;
+    vec_fv[0] = 0.0;
+    vec_fv[1] = ixs * txx + iys * txy;
+    vec_fv[2] = ixs * txy + iys * tyy;
+    vec_fv[3] = ixs * (u * txx + v * txy - qx) + iys * (u * txy + v * tyy - qy);
+  }
+
+
+
+/// v/r in axisymmetric flow, 0 on the axis or otherwise
+#[inline]
+pub(super) fn calc_v_r(v: &f64, r: &f64, axisymmetric: &bool) -> f64 {
+  if *axisymmetric && *r != 0.0 {
+    v / r
+  } else {
+    0.0
+  }
+}
+
+
+
+/// hoop stress of axisymmetric flow at cell (NB+i, NB+j)
+/// with eos frozen by phi for mixture
+#[allow(clippy::too_many_arguments)]
+pub fn calc_hoop_stress<T: EoS, const NI: usize, const NJ: usize, const NB: usize>(i: &usize, j: &usize,
+  rho: &[[f64; NJ]; NI], u: &[[f64; NJ]; NI], v: &[[f64; NJ]; NI],
+  e: &[[f64; NJ]; NI], phi: &[f64; NS], coord: &GenStructCoord::<NI,NJ,NB>, eos: &T, transport: &TransportProp) -> f64 {
+  let (i, j) = (*i, *j);
+  let ic = NB + i;
+  let jc = NB + j;
+  let s_a = 0.25 * (coord.s[NB+i-1][NB+j-1] + coord.s[NB+i][NB+j-1] + coord.s[NB+i-1][NB+j] + coord.s[NB+i][NB+j]);
+  let u_i = fnd::central_diff4(&u[ic-2][jc], &u[ic-1][jc], &u[ic+1][jc], &u[ic+2][jc]);
+  let u_j = fnd::central_diff4(&u[ic][jc-2], &u[ic][jc-1], &u[ic][jc+1], &u[ic][jc+2]);
+  let v_i = fnd::central_diff4(&v[ic-2][jc], &v[ic-1][jc], &v[ic+1][jc], &v[ic+2][jc]);
+  let v_j = fnd::central_diff4(&v[ic][jc-2], &v[ic][jc-1], &v[ic][jc+1], &v[ic][jc+2]);
+  let ux = (coord.ixs[i+1][j+1] * u_i + coord.jxs[i+1][j+1] * u_j) / s_a;
+  let vy = (coord.iys[i+1][j+1] * v_i + coord.jys[i+1][j+1] * v_j) / s_a;
+  let v_r = v[ic][jc] / coord.y[ic][jc];
+  let mu = transport.calc_mu(&eos.frozen(phi).calc_temp(&rho[ic][jc], &u[ic][jc], &v[ic][jc], &e[ic][jc]));
+  mu * (2.0 * v_r - 2.0 / 3.0 * (ux + vy + v_r))
+}
+
+
+
+impl<const NI: usize, const NJ: usize, const NB: usize> super::super::super::EulerEq::<NI,NJ,NB> {
+  /// subtract viscous flux from numerical flux in arr_fi and arr_fj
+  /// derivatives along the face by central_diff3,
+  /// and across the face by averaging central_diff4 of both sides
+  /// for mixture, eos is frozen in each cell by phi, and arr_fi_r and arr_fj_r are also subtracted
+  #[allow(clippy::too_many_arguments)]
+  pub(super) fn sub_flux_visc<T: EoS>(&mut self,
+  rho: &[[f64; NJ]; NI], u: &[[f64; NJ]; NI], v: &[[f64; NJ]; NI],
+  e: &[[f64; NJ]; NI], phi: &[[[f64; NS]; NJ]; NI], coord: &GenStructCoord::<NI,NJ,NB>, eos: &T, transport: &TransportProp) {
+    let mixture = eos.is_mixture();
+    let temp = |i: usize, j: usize| eos.frozen(&phi[i][j]).calc_temp(&rho[i][j], &u[i][j], &v[i][j], &e[i][j]);
+    let cp = |i: usize, j: usize| eos.frozen(&phi[i][j]).calc_cp(&rho[i][j], &u[i][j], &v[i][j], &e[i][j]);
+
+    //--------------------i-direction---------------------
+    // evaluating viscous flux at (i+0.5,j)
+    for i in 0..(NI-2*NB+1) {
+      for j in 0..(NJ-2*NB) {
+        let il = NB + i - 1;
+        let ir = NB + i;
+        let jc = NB + j;
+        let s_a = 0.5 * (coord.s[NB+i-1][NB+j-1] + coord.s[NB+i-1][NB+j]);
+        let ixs_a = 0.5 * (coord.ixs[i][j+1] + coord.ixs[i+1][j+1]);
+        let iys_a = 0.5 * (coord.iys[i][j+1] + coord.iys[i+1][j+1]);
+        let jxs_a = 0.5 * (coord.jxs[i][j+1] + coord.jxs[i+1][j+1]);
+        let jys_a = 0.5 * (coord.jys[i][j+1] + coord.jys[i+1][j+1]);
+        // derivatives in computational space
+        let u_i = fnd::central_diff3(&u[il-1][jc], &u[il][jc], &u[ir][jc], &u[ir+1][jc]);
+        let v_i = fnd::central_diff3(&v[il-1][jc], &v[il][jc], &v[ir][jc], &v[ir+1][jc]);
+        let t_i = fnd::central_diff3(&temp(il-1, jc), &temp(il, jc), &temp(ir, jc), &temp(ir+1, jc));
+        let u_j = 0.5 * (fnd::central_diff4(&u[il][jc-2], &u[il][jc-1], &u[il][jc+1], &u[il][jc+2])
+          + fnd::central_diff4(&u[ir][jc-2], &u[ir][jc-1], &u[ir][jc+1], &u[ir][jc+2]));
+        let v_j = 0.5 * (fnd::central_diff4(&v[il][jc-2], &v[il][jc-1], &v[il][jc+1], &v[il][jc+2])
+          + fnd::central_diff4(&v[ir][jc-2], &v[ir][jc-1], &v[ir][jc+1], &v[ir][jc+2]));
+        let t_j = 0.5 * (fnd::central_diff4(&temp(il, jc-2), &temp(il, jc-1), &temp(il, jc+1), &temp(il, jc+2))
+          + fnd::central_diff4(&temp(ir, jc-2), &temp(ir, jc-1), &temp(ir, jc+1), &temp(ir, jc+2)));
+        // values at the face
+        let u_a = 0.5 * (u[il][jc] + u[ir][jc]);
+        let v_a = 0.5 * (v[il][jc] + v[ir][jc]);
+        let v_r = calc_v_r(&v_a, &(0.5 * (coord.y[il][jc] + coord.y[ir][jc])), &coord.axisymmetric);
+        let mu = transport.calc_mu(&(0.5 * (temp(il, jc) + temp(ir, jc))));
+        let kappa = transport.calc_kappa(&mu, &(0.5 * (cp(il, jc) + cp(ir, jc))));
+        let mut vec_fv = [0.0; NF];
+        calc_flux_visc(&u_a, &v_a,
+          &((ixs_a * u_i + jxs_a * u_j) / s_a), &((iys_a * u_i + jys_a * u_j) / s_a),
+          &((ixs_a * v_i + jxs_a * v_j) / s_a), &((iys_a * v_i + jys_a * v_j) / s_a), &v_r,
+          &((ixs_a * t_i + jxs_a * t_j) / s_a), &((iys_a * t_i + jys_a * t_j) / s_a),
+          &mu, &kappa, &ixs_a, &iys_a, &mut vec_fv);
+        for (k, d) in vec_fv.iter().enumerate() {
+          self.arr_fi[i][j][k] -= d;
+          if mixture {
+            self.arr_fi_r[i][j][k] -= d;
+          }
+        }
+      }
+    }
+
+
+    //--------------------j-direction---------------------
+    // evaluating viscous flux at (i,j+0.5)
+    for i in 0..(NI-2*NB) {
+      for j in 0..(NJ-2*NB+1) {
+        let ic = NB + i;
+        let jl = NB + j - 1;
+        let jr = NB + j;
+        let s_a = 0.5 * (coord.s[NB+i-1][NB+j-1] + coord.s[NB+i][NB+j-1]);
+        let ixs_a = 0.5 * (coord.ixs[i+1][j] + coord.ixs[i+1][j+1]);
+        let iys_a = 0.5 * (coord.iys[i+1][j] + coord.iys[i+1][j+1]);
+        let jxs_a = 0.5 * (coord.jxs[i+1][j] + coord.jxs[i+1][j+1]);
+        let jys_a = 0.5 * (coord.jys[i+1][j] + coord.jys[i+1][j+1]);
+        if coord.is_axis_j(&i, &j) {
+          continue;
+        }
+        // derivatives in computational space
+        let u_j = fnd::central_diff3(&u[ic][jl-1], &u[ic][jl], &u[ic][jr], &u[ic][jr+1]);
+        let v_j = fnd::central_diff3(&v[ic][jl-1], &v[ic][jl], &v[ic][jr], &v[ic][jr+1]);
+        let t_j = fnd::central_diff3(&temp(ic, jl-1), &temp(ic, jl), &temp(ic, jr), &temp(ic, jr+1));
+        let u_i = 0.5 * (fnd::central_diff4(&u[ic-2][jl], &u[ic-1][jl], &u[ic+1][jl], &u[ic+2][jl])
+          + fnd::central_diff4(&u[ic-2][jr], &u[ic-1][jr], &u[ic+1][jr], &u[ic+2][jr]));
+        let v_i = 0.5 * (fnd::central_diff4(&v[ic-2][jl], &v[ic-1][jl], &v[ic+1][jl], &v[ic+2][jl])
+          + fnd::central_diff4(&v[ic-2][jr], &v[ic-1][jr], &v[ic+1][jr], &v[ic+2][jr]));
+        let t_i = 0.5 * (fnd::central_diff4(&temp(ic-2, jl), &temp(ic-1, jl), &temp(ic+1, jl), &temp(ic+2, jl))
+          + fnd::central_diff4(&temp(ic-2, jr), &temp(ic-1, jr), &temp(ic+1, jr), &temp(ic+2, jr)));
+        // values at the face
+        let u_a = 0.5 * (u[ic][jl] + u[ic][jr]);
+        let v_a = 0.5 * (v[ic][jl] + v[ic][jr]);
+        let v_r = calc_v_r(&v_a, &(0.5 * (coord.y[ic][jl] + coord.y[ic][jr])), &coord.axisymmetric);
+        let mu = transport.calc_mu(&(0.5 * (temp(ic, jl) + temp(ic, jr))));
+        let kappa = transport.calc_kappa(&mu, &(0.5 * (cp(ic, jl) + cp(ic, jr))));
+        let mut vec_fv = [0.0; NF];
+        calc_flux_visc(&u_a, &v_a,
+          &((ixs_a * u_i + jxs_a * u_j) / s_a), &((iys_a * u_i + jys_a * u_j) / s_a),
+          &((ixs_a * v_i + jxs_a * v_j) / s_a), &((iys_a * v_i + jys_a * v_j) / s_a), &v_r,
+          &((ixs_a * t_i + jxs_a * t_j) / s_a), &((iys_a * t_i + jys_a * t_j) / s_a),
+          &mu, &kappa, &jxs_a, &jys_a, &mut vec_fv);
+        for (k, d) in vec_fv.iter().enumerate() {
+          self.arr_fj[i][j][k] -= d;
+          if mixture {
+            self.arr_fj_r[i][j][k] -= d;
+          }
+        }
+      }
+    }
+  }
+}
+
+
+
+#[test]
+fn test_viscous_flux() {
+  // viscous flux of a linear velocity and temperature field (uniform stress and heat flux)
+  // on a skewed grid should equal the analytic one at every face
+  const NI: usize = 12;
+  const NJ: usize = 12;
+  const NB: usize = 4;
+  let xy = |i: usize, j: usize| (0.1 * i as f64 + 0.05 * j as f64, 0.02 * i as f64 + 0.08 * j as f64);
+  let mut fluid = super::super::super::IdealGas::<NI,NJ,NB,_>::new_for_test(super::super::super::IdealEoS::new(&1.4, &2.5), xy, &false);
+  fluid.transport = TransportProp::new(&50.0, &0.72, "constant", &1.0, &0.3830, &0.76);
+  let (ux, uy, vx, vy, tx, ty) = (0.3, 0.5, 0.2, -0.1, 0.2, 0.4);
+  for i in 0..NI {
+    for j in 0..NJ {
+      let (x, y) = xy(i, j);
+      let (u, v, temp) = (ux * x + uy * y, vx * x + vy * y, 1.0 + tx * x + ty * y);
+      fluid.basic.rho[i][j] = 1.3;
+      fluid.basic.u[i][j] = u;
+      fluid.basic.v[i][j] = v;
+      fluid.basic.e[i][j] = 1.3 * (2.5 * temp + 0.5 * (u * u + v * v));
+    }
+  }
+  let mu = 1.0 / 50.0;
+  let kappa = mu * 1.4 * 2.5 / 0.72;
+  let div = ux + vy;
+  let (txx, tyy, txy) = (mu * (2.0 * ux - 2.0 / 3.0 * div), mu * (2.0 * vy - 2.0 / 3.0 * div), mu * (uy + vx));
+  let (qx, qy) = (-kappa * tx, -kappa * ty);
+  let (basic, coord) = (&fluid.basic, &fluid.coord);
+  fluid.eq.sub_flux_visc(&basic.rho, &basic.u, &basic.v, &basic.e, &basic.phi, coord, &fluid.eos, &fluid.transport);
+  let check = |vec_f: &[f64; NF], xs: f64, ys: f64, u: f64, v: f64| {
+    let vec_fv = [0.0, xs * txx + ys * txy, xs * txy + ys * tyy, xs * (u * txx + v * txy - qx) + ys * (u * txy + v * tyy - qy)];
+    for k in 0..4 {
+      assert!((vec_f[k] + vec_fv[k]).abs() < 1.0e-12, "{:?} {:?}", vec_f, vec_fv);
+    }
+  };
+  for i in 0..(NI-2*NB+1) {
+    for j in 0..(NJ-2*NB) {
+      let (il, ir, jc) = (NB + i - 1, NB + i, NB + j);
+      check(&fluid.eq.arr_fi[i][j], 0.5 * (coord.ixs[i][j+1] + coord.ixs[i+1][j+1]), 0.5 * (coord.iys[i][j+1] + coord.iys[i+1][j+1]),
+        0.5 * (basic.u[il][jc] + basic.u[ir][jc]), 0.5 * (basic.v[il][jc] + basic.v[ir][jc]));
+    }
+  }
+  for i in 0..(NI-2*NB) {
+    for j in 0..(NJ-2*NB+1) {
+      let (ic, jl, jr) = (NB + i, NB + j - 1, NB + j);
+      check(&fluid.eq.arr_fj[i][j], 0.5 * (coord.jxs[i+1][j] + coord.jxs[i+1][j+1]), 0.5 * (coord.jys[i+1][j] + coord.jys[i+1][j+1]),
+        0.5 * (basic.u[ic][jl] + basic.u[ic][jr]), 0.5 * (basic.v[ic][jl] + basic.v[ic][jr]));
+    }
+  }
+}
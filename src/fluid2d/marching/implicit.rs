@@ -0,0 +1,126 @@
+use super::super::eos::EoS;
+use super::eq::{euler::conserved, passive};
+use super::super::{NF, NS};
+
+
+
+/// spectral radius of flux Jacobian in the direction of (ixs, iys)
+#[inline]
+fn spectral_radius<T: EoS>(rho: &f64, u: &f64, v: &f64, e: &f64,
+  ixs: &f64, iys: &f64, eos: &T) -> f64 {
+  (ixs * u + iys * v).abs() + eos.calc_cs(rho, u, v, e) * (ixs * ixs + iys * iys).sqrt()
+}
+
+
+
+/// calc (A +- rho_A I) dq / 2 without forming the flux Jacobian A
+/// as (F(q + dq) - F(q) +- rho_A dq) / 2
+#[inline]
+#[allow(clippy::too_many_arguments)]
+fn split_flux_diff<T: EoS>(rho: &f64, u: &f64, v: &f64, e: &f64, phi: &[f64; NS], dq: &[f64; NF],
+  ixs: &f64, iys: &f64, sign: &f64, eos: &T, vec_df: &mut [f64; NF]) {
+  let mut vec_q = [0.0; NF];
+  conserved::calc_conservative(rho, u, v, e, &1.0, &mut vec_q);
+  passive::calc_conservative(rho, phi, &1.0, &mut vec_q);
+  for k in 0..NF {
+    vec_q[k] += dq[k];
+  }
+  let mut rho_d = 0.0;
+  let mut u_d = 0.0;
+  let mut v_d = 0.0;
+  let mut e_d = 0.0;
+  let mut phi_d = [0.0; NS];
+  conserved::calc_basic(&vec_q, &1.0, &mut rho_d, &mut u_d, &mut v_d, &mut e_d);
+  passive::calc_basic(&vec_q, &mut phi_d);
+  let mut vec_f = [0.0; NF];
+  conserved::calc_flux_conv(rho, u, v, e, ixs, iys, eos, &mut vec_f);
+  passive::calc_flux(phi, phi, &mut vec_f);
+  let mut vec_f_d = [0.0; NF];
+  conserved::calc_flux_conv(&rho_d, &u_d, &v_d, &e_d, ixs, iys, eos, &mut vec_f_d);
+  passive::calc_flux(&phi_d, &phi_d, &mut vec_f_d);
+  let sp = spectral_radius(rho, u, v, e, ixs, iys, eos);
+  for k in 0..NF {
+    vec_df[k] = 0.5 * (vec_f_d[k] - vec_f[k] + sign * sp * dq[k]);
+  }
+}
+
+
+
+impl<const NI: usize, const NJ: usize, const NB: usize, T: EoS> super::super::IdealGas::<NI,NJ,NB,T> {
+  /// solve (D + L) D^-1 (D + U) dq = RHS approximately by LU-SGS method
+  /// RHS is given in arr_q[0], and dq is returned in arr_q[1]
+  /// D = s/dtau + s * diag_coeff + rho_A + rho_B,
+  /// where dtau is the local time step with cfl_coeff
+  pub(super) fn solve_lusgs(&mut self, cfl_coeff: &f64, diag_coeff: &f64) {
+    // forward sweep
+    for i in 0..(NI-2*NB) {
+      for j in 0..(NJ-2*NB) {
+        let mut vec_dq = self.arr_q[0][i][j];
+        let mut vec_df = [0.0; NF];
+        if i > 0 {
+          split_flux_diff(&self.basic.rho[NB+i-1][NB+j], &self.basic.u[NB+i-1][NB+j], &self.basic.v[NB+i-1][NB+j], &self.basic.e[NB+i-1][NB+j], &self.basic.phi[NB+i-1][NB+j],
+            &self.arr_q[1][i-1][j], &self.coord.ixs[i][j+1], &self.coord.iys[i][j+1], &1.0, &self.eos.frozen(&self.basic.phi[NB+i-1][NB+j]), &mut vec_df);
+          for k in 0..NF {
+            vec_dq[k] += vec_df[k];
+          }
+        }
+        if j > 0 {
+          split_flux_diff(&self.basic.rho[NB+i][NB+j-1], &self.basic.u[NB+i][NB+j-1], &self.basic.v[NB+i][NB+j-1], &self.basic.e[NB+i][NB+j-1], &self.basic.phi[NB+i][NB+j-1],
+            &self.arr_q[1][i][j-1], &self.coord.jxs[i+1][j], &self.coord.jys[i+1][j], &1.0, &self.eos.frozen(&self.basic.phi[NB+i][NB+j-1]), &mut vec_df);
+          for k in 0..NF {
+            vec_dq[k] += vec_df[k];
+          }
+        }
+        let diag = self.calc_lusgs_diag(&i, &j, cfl_coeff, diag_coeff);
+        for (dq, q) in self.arr_q[1][i][j].iter_mut().zip(&vec_dq) {
+          *dq = q / diag;
+        }
+      }
+    }
+    // backward sweep
+    for i in (0..(NI-2*NB)).rev() {
+      for j in (0..(NJ-2*NB)).rev() {
+        let mut vec_corr = [0.0; NF];
+        let mut vec_df = [0.0; NF];
+        if i < NI-2*NB-1 {
+          split_flux_diff(&self.basic.rho[NB+i+1][NB+j], &self.basic.u[NB+i+1][NB+j], &self.basic.v[NB+i+1][NB+j], &self.basic.e[NB+i+1][NB+j], &self.basic.phi[NB+i+1][NB+j],
+            &self.arr_q[1][i+1][j], &self.coord.ixs[i+2][j+1], &self.coord.iys[i+2][j+1], &-1.0, &self.eos.frozen(&self.basic.phi[NB+i+1][NB+j]), &mut vec_df);
+          for k in 0..NF {
+            vec_corr[k] += vec_df[k];
+          }
+        }
+        if j < NJ-2*NB-1 {
+          split_flux_diff(&self.basic.rho[NB+i][NB+j+1], &self.basic.u[NB+i][NB+j+1], &self.basic.v[NB+i][NB+j+1], &self.basic.e[NB+i][NB+j+1], &self.basic.phi[NB+i][NB+j+1],
+            &self.arr_q[1][i][j+1], &self.coord.jxs[i+1][j+2], &self.coord.jys[i+1][j+2], &-1.0, &self.eos.frozen(&self.basic.phi[NB+i][NB+j+1]), &mut vec_df);
+          for k in 0..NF {
+            vec_corr[k] += vec_df[k];
+          }
+        }
+        let diag = self.calc_lusgs_diag(&i, &j, cfl_coeff, diag_coeff);
+        for (dq, corr) in self.arr_q[1][i][j].iter_mut().zip(&vec_corr) {
+          *dq -= corr / diag;
+        }
+      }
+    }
+  }
+
+
+  /// diagonal element of LU-SGS at cell (NB+i, NB+j)
+  /// the local pseudo-time step includes the diffusive limit if viscous
+  #[inline]
+  fn calc_lusgs_diag(&self, i: &usize, j: &usize, cfl_coeff: &f64, diag_coeff: &f64) -> f64 {
+    let (i, j) = (*i, *j);
+    let rho = &self.basic.rho[NB+i][NB+j];
+    let u = &self.basic.u[NB+i][NB+j];
+    let v = &self.basic.v[NB+i][NB+j];
+    let e = &self.basic.e[NB+i][NB+j];
+    let eos = self.eos.frozen(&self.basic.phi[NB+i][NB+j]);
+    let s_a = 0.25 * (self.coord.s[NB+i-1][NB+j-1] + self.coord.s[NB+i][NB+j-1] + self.coord.s[NB+i-1][NB+j] + self.coord.s[NB+i][NB+j]);
+    // local time step
+    let (rate_conv, rate_diff) = self.calc_rates(&i, &j);
+    let dtau = cfl_coeff / (rate_conv + rate_diff);
+    s_a / dtau + s_a * diag_coeff
+    + spectral_radius(rho, u, v, e, &self.coord.ixs[i+1][j+1], &self.coord.iys[i+1][j+1], &eos)
+    + spectral_radius(rho, u, v, e, &self.coord.jxs[i+1][j+1], &self.coord.jys[i+1][j+1], &eos)
+  }
+}
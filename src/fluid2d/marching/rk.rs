@@ -0,0 +1,161 @@
+/// maximum number of stages of explicit Runge-Kutta schemes
+pub const MAX_STAGE: usize = 5;
+
+/// explicit Runge-Kutta scheme selected by name
+#[allow(clippy::large_enum_variant)]
+pub enum RKScheme {
+  /// Shu-Osher form
+  /// u(i) = sum_{k<i} alpha[i-1][k] * u(k) + dt * beta[i-1][k] * L(u(k)), for i = 1..=n_stage
+  ShuOsher {
+    n_stage: usize,
+    alpha: [[f64; MAX_STAGE]; MAX_STAGE],
+    beta: [[f64; MAX_STAGE]; MAX_STAGE],
+  },
+  /// low-storage 2N form of Williamson
+  /// dq = a[i] * dq + dt * L(q), q = q + b[i] * dq, for i = 0..n_stage
+  LowStorage2N {
+    n_stage: usize,
+    a: [f64; MAX_STAGE],
+    b: [f64; MAX_STAGE],
+  },
+}
+
+impl RKScheme {
+  /// construct scheme from its name
+  pub fn new(rk_scheme: &str) -> Self {
+    match rk_scheme {
+      "Euler" => RKScheme::shu_osher(&[&[1.0]], &[&[1.0]]),
+      "SSPRK2" => RKScheme::shu_osher(
+        &[&[1.0], &[0.5, 0.5]],
+        &[&[1.0], &[0.0, 0.5]]),
+      "SSPRK3" => RKScheme::shu_osher(
+        &[&[1.0], &[0.75, 0.25], &[1.0 / 3.0, 0.0, 2.0 / 3.0]],
+        &[&[1.0], &[0.0, 0.25], &[0.0, 0.0, 2.0 / 3.0]]),
+      // Spiteri & Ruuth (2002)
+      "SSPRK54" => RKScheme::shu_osher(
+        &[&[1.0],
+          &[0.444370493651235, 0.555629506348765],
+          &[0.620101851488403, 0.0, 0.379898148511597],
+          &[0.178079954393132, 0.0, 0.0, 0.821920045606868],
+          &[0.0, 0.0, 0.517231671970585, 0.096059710526147, 0.386708617503269]],
+        &[&[0.391752226571890],
+          &[0.0, 0.368410593050371],
+          &[0.0, 0.0, 0.251891774271694],
+          &[0.0, 0.0, 0.0, 0.544974750228521],
+          &[0.0, 0.0, 0.0, 0.063692468666290, 0.226007483236906]]),
+      // classical 4th order Runge-Kutta rewritten in Shu-Osher form
+      "RK4" => RKScheme::shu_osher(
+        &[&[1.0], &[1.0, 0.0], &[1.0, 0.0, 0.0], &[-1.0 / 3.0, 1.0 / 3.0, 2.0 / 3.0, 1.0 / 3.0]],
+        &[&[0.5], &[0.0, 0.5], &[0.0, 0.0, 1.0], &[0.0, 0.0, 0.0, 1.0 / 6.0]]),
+      // Williamson (1980), 3rd order
+      "LSRK3" => RKScheme::low_storage_2n(
+        &[0.0, -5.0 / 9.0, -153.0 / 128.0],
+        &[1.0 / 3.0, 15.0 / 16.0, 8.0 / 15.0]),
+      // Carpenter & Kennedy (1994), 4th order with 5 stages
+      "LSRK54" => RKScheme::low_storage_2n(
+        &[0.0,
+          -567301805773.0 / 1357537059087.0,
+          -2404267990393.0 / 2016746695238.0,
+          -3550918686646.0 / 2091501179385.0,
+          -1275806237668.0 / 842570457699.0],
+        &[1432997174477.0 / 9575080441755.0,
+          5161836677717.0 / 13612068292357.0,
+          1720146321549.0 / 2090206949498.0,
+          3134564353537.0 / 4481467310338.0,
+          2277821191437.0 / 14882151754819.0]),
+      _ => panic!("Time integrator not specified."),
+    }
+  }
+
+
+  /// construct Shu-Osher form from lower-triangular coefficients
+  fn shu_osher(alpha: &[&[f64]], beta: &[&[f64]]) -> Self {
+    let mut a = [[0.0; MAX_STAGE]; MAX_STAGE];
+    let mut b = [[0.0; MAX_STAGE]; MAX_STAGE];
+    for i in 0..alpha.len() {
+      a[i][..alpha[i].len()].copy_from_slice(alpha[i]);
+      b[i][..beta[i].len()].copy_from_slice(beta[i]);
+    }
+    RKScheme::ShuOsher { n_stage: alpha.len(), alpha: a, beta: b }
+  }
+
+
+  /// construct low-storage 2N form from coefficients
+  fn low_storage_2n(a: &[f64], b: &[f64]) -> Self {
+    let mut aa = [0.0; MAX_STAGE];
+    let mut bb = [0.0; MAX_STAGE];
+    aa[..a.len()].copy_from_slice(a);
+    bb[..b.len()].copy_from_slice(b);
+    RKScheme::LowStorage2N { n_stage: a.len(), a: aa, b: bb }
+  }
+
+
+  /// assign work arrays to stage values u(k) and RHS L(u(k)) of Shu-Osher form
+  /// work arrays are reused as soon as the values they hold are no longer referred to
+  /// returns the number of work arrays needed
+  pub fn allocate_registers(&self, reg_u: &mut [usize; MAX_STAGE + 1],
+    reg_l: &mut [usize; MAX_STAGE]) -> usize {
+    match self {
+      RKScheme::ShuOsher { n_stage, alpha, beta } => {
+        // the last stage referring to u(k) and L(u(k))
+        // values referred to by nothing are released at the stage they are made
+        let mut last_u = [0; MAX_STAGE + 1];
+        let mut last_l = [0; MAX_STAGE];
+        for k in 0..*n_stage {
+          last_u[k] = k;
+          last_l[k] = k + 1;
+        }
+        last_u[*n_stage] = *n_stage;
+        for i in 1..=*n_stage {
+          for k in 0..i {
+            if alpha[i-1][k] != 0.0 {
+              last_u[k] = i;
+            }
+            if beta[i-1][k] != 0.0 {
+              last_l[k] = i;
+            }
+          }
+        }
+        let mut free: Vec<usize> = Vec::new();
+        let mut n_reg = 0;
+        let mut alloc = |free: &mut Vec<usize>| free.pop().unwrap_or_else(|| {
+          n_reg += 1;
+          n_reg - 1
+        });
+        reg_u[0] = alloc(&mut free);
+        for i in 1..=*n_stage {
+          reg_l[i-1] = alloc(&mut free);
+          // u(i) may overwrite the values referred to for the last time at stage i
+          for k in 0..i {
+            if last_u[k] == i {
+              free.push(reg_u[k]);
+            }
+            if last_l[k] == i {
+              free.push(reg_l[k]);
+            }
+          }
+          reg_u[i] = alloc(&mut free);
+          if last_u[i] == i {
+            free.push(reg_u[i]);
+          }
+        }
+        n_reg
+      },
+      RKScheme::LowStorage2N { .. } => {
+        reg_u[0] = 0;
+        reg_l[0] = 1;
+        2
+      },
+    }
+  }
+}
+
+
+#[test]
+fn test_registers() {
+  let mut reg_u = [0; MAX_STAGE + 1];
+  let mut reg_l = [0; MAX_STAGE];
+  for (rk_scheme, n_reg) in [("Euler", 2), ("SSPRK2", 3), ("SSPRK3", 3), ("SSPRK54", 5), ("RK4", 5), ("LSRK3", 2), ("LSRK54", 2)] {
+    assert_eq!(RKScheme::new(rk_scheme).allocate_registers(&mut reg_u, &mut reg_l), n_reg);
+  }
+}
@@ -0,0 +1,81 @@
+impl super::Reference {
+  /// reference pressure, also of total energy per volume
+  pub const fn pressure(&self) -> f64 {
+    self.density * self.velocity * self.velocity
+  }
+  /// reference time
+  pub const fn time(&self) -> f64 {
+    self.length / self.velocity
+  }
+  /// reference acceleration
+  pub const fn acceleration(&self) -> f64 {
+    self.velocity * self.velocity / self.length
+  }
+  /// reference viscosity, the inverse of Reynolds number in nondimensional form
+  pub const fn viscosity(&self) -> f64 {
+    self.density * self.velocity * self.length
+  }
+  /// Reynolds number from viscosity in SI units (0 for inviscid flow)
+  pub const fn reynolds(&self, mu: &f64) -> f64 {
+    if *mu > 0.0 { self.viscosity() / *mu } else { 0.0 }
+  }
+  /// reference specific heat, also of gas constant
+  pub const fn specific_heat(&self) -> f64 {
+    self.velocity * self.velocity / self.temperature
+  }
+  /// nondimensional specific heats from those in SI units
+  #[allow(dead_code)]
+  pub const fn nondim_cv<const N: usize>(&self, cv: &[f64; N]) -> [f64; N] {
+    let mut cv_nd = [0.0; N];
+    let mut k = 0;
+    while k < N {
+      cv_nd[k] = cv[k] / self.specific_heat();
+      k += 1;
+    }
+    cv_nd
+  }
+  /// description of reference state for settings file
+  pub fn describe(&self) -> String {
+    format!("reference state (SI units).
+    length = {}
+    density = {}
+    velocity = {}
+    temperature = {}
+    pressure = {}
+    time = {}
+    acceleration = {}
+    viscosity = {}
+    specific heat = {}", self.length, self.density, self.velocity, self.temperature,
+    self.pressure(), self.time(), self.acceleration(), self.viscosity(), self.specific_heat())
+  }
+}
+
+
+
+impl<const NI: usize, const NJ: usize, const NB: usize> super::GenStructCoord::<NI,NJ,NB> {
+  /// convert coordinate in SI units into nondimensional one
+  pub(super) fn nondimensionalize(&mut self, reference: &super::Reference) {
+    for i in 0..NI {
+      for j in 0..NJ {
+        self.x[i][j] /= reference.length;
+        self.y[i][j] /= reference.length;
+      }
+    }
+  }
+}
+
+
+
+impl<const NI: usize, const NJ: usize, const NB: usize> super::BasicVarHD::<NI,NJ,NB> {
+  /// convert basic variables in SI units into nondimensional ones
+  pub(super) fn nondimensionalize(&mut self, reference: &super::Reference) {
+    for i in 0..NI {
+      for j in 0..NJ {
+        self.rho[i][j] /= reference.density;
+        self.u[i][j] /= reference.velocity;
+        self.v[i][j] /= reference.velocity;
+        self.e[i][j] /= reference.pressure();
+      }
+    }
+  }
+}
@@ -1,26 +1,39 @@
 use std::fs::File;
 use std::io::Write;
-use super::IdealEoS;
+use super::{eos::EoS, ArtificialDissipation, BodyForce, HybridScheme, PositivityLimiter, Reference, TransportProp};
 
 
 pub trait Settings {
-  fn output(&self, f_settings: &str, t_max: &f64, n_out: &u64, eos: &IdealEoS);
+  #[allow(clippy::too_many_arguments)]
+  fn output<T: EoS>(&self, f_settings: &str, t_max: &f64, n_out: &u64, axisymmetric: &bool, eos: &T, transport: &TransportProp, force: &BodyForce, limiter: &PositivityLimiter, hybrid: &HybridScheme, artificial: &ArtificialDissipation, reference: &Reference);
 }
 
 
 impl<const NI: usize, const NJ: usize, const NB: usize> Settings 
 for super::SetStructEuler::<NI,NJ,NB> {
-  fn output(&self, f_settings: &str, t_max: &f64, n_out: &u64, eos: &IdealEoS) {
+  fn output<T: EoS>(&self, f_settings: &str, t_max: &f64, n_out: &u64, axisymmetric: &bool, eos: &T, transport: &TransportProp, force: &BodyForce, limiter: &PositivityLimiter, hybrid: &HybridScheme, artificial: &ArtificialDissipation, reference: &Reference) {
     let mut file = File::create(f_settings).unwrap();
-    let line = format!("Solving Euler eq. with ideal gas eos.
-    gamma = {}
+    let eq = if transport.re > 0.0 { "Navier-Stokes" } else { "Euler" };
+    let geom = if *axisymmetric { "axisymmetric " } else { "" };
+    let line = format!("Solving {}{} eq. with {}
+    Re = {}
+    Pr = {}
+    viscosity = {} (T_ref = {}, S/T_ref = {}, omega = {})
+    gravity = ({}, {}), well-balanced = {}
     NI = {}
     NJ = {}
     NB = {}
+    NS = {}
     Tmax = {}
     Nout = {}
+    {}
+    {}
+    {}
+    {}
     
-    ", eos.gamma, NI, NJ, NB, t_max, n_out);
+    ", geom, eq, eos.describe(), transport.re, transport.pr,
+    transport.law, transport.t_ref * reference.temperature, transport.s_suth, transport.omega,
+    force.gx * reference.acceleration(), force.gy * reference.acceleration(), force.balanced, NI, NJ, NB, super::NS, t_max * reference.time(), n_out, limiter.describe(), hybrid.describe(), artificial.describe(), reference.describe());
     write!(file, "{}", line).unwrap();
   }
 }
\ No newline at end of file
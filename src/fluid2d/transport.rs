@@ -0,0 +1,33 @@
+pub trait Transport {
+  /// whether viscous and heat-conduction terms are solved
+  fn is_viscous(&self) -> bool;
+  /// calc viscosity from temperature
+  fn calc_mu(&self, temp: &f64) -> f64;
+  /// calc thermal conductivity from viscosity and specific heat at constant pressure
+  fn calc_kappa(&self, mu: &f64, cp: &f64) -> f64;
+}
+
+impl Transport for super::TransportProp {
+  /// whether viscous and heat-conduction terms are solved
+  #[inline]
+  fn is_viscous(&self) -> bool {
+    self.re > 0.0
+  }
+  /// calc viscosity from temperature
+  /// normalized so that mu = 1/re at t_ref
+  #[inline]
+  fn calc_mu(&self, temp: &f64) -> f64 {
+    let tr = temp / self.t_ref;
+    match self.law {
+      "constant" => 1.0 / self.re,
+      "Sutherland" => tr * tr.sqrt() * (1.0 + self.s_suth) / (tr + self.s_suth) / self.re,
+      "power" => tr.powf(self.omega) / self.re,
+      _ => panic!("Viscosity law not specified."),
+    }
+  }
+  /// calc thermal conductivity from viscosity and specific heat at constant pressure
+  #[inline]
+  fn calc_kappa(&self, mu: &f64, cp: &f64) -> f64 {
+    mu * cp / self.pr
+  }
+}
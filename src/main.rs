@@ -1,8 +1,10 @@
-use crate::fluid2d::marching::Marching;
+use crate::fluid2d::{eos::EoS, marching::Marching};
 
 mod fluid2d;
 
-use fluid2d::{basic_var::BasicVar, settings::Settings};
+use fluid2d::{basic_var::BasicVar, settings::Settings, ArtificialDissipation, BodyForce, HybridScheme, IdealEoS, PositivityLimiter, Reference, TimeStepControl, TransportProp};
+use std::fs::File;
+use std::io::{BufWriter, Write};
 
 fn main() {
     const NI: usize = 408;
@@ -13,46 +15,212 @@ fn main() {
     let dir_o: &str = &(DIR.to_string() + "");
     let f_coordinate: &str = &(DIR.to_string() + "coordinate.dat");
     let f_settings: &str = &(DIR.to_string() + "settings.dat");
+    let f_residual: &str = &(DIR.to_string() + "residual.dat");
+    let f_conservation: &str = &(DIR.to_string() + "conservation.csv");
+    // reference length, density, velocity and temperature in SI units
+    // files of coordinate and basic variables and the constants below are in SI units,
+    // and nondimensional if all unity, converted by this reference state
+    // (coefficients of StiffenedGasEoS, CubicEoS or the eos table replacing IdealEoS are converted in the same way,
+    // e.g. &(P_INF / REFERENCE.pressure()) and &(T_C / REFERENCE.temperature))
+    const REFERENCE: Reference = Reference::new(&1.0, &1.0, &1.0, &1.0);
+    // specific heat ratio and specific heat at constant volume in SI units
     const GAMMA: f64 = 1.4;
+    const CV: f64 = 1.0;
+    // for mixture of two gases, build with the feature "mixture" (one passive scalar for the mass fraction of species 1)
+    // and replace IdealEoS below with MixtureEoS<2> given by
+    // MixtureEoS::new(&GAMMA_SP, &REFERENCE.nondim_cv(&CV_SP)) with e.g. GAMMA_SP = [1.4, 5.0 / 3.0] and CV_SP = [717.5, 3115.6] for air/helium
+    // viscosity at temperature T_REF, viscous and heat-conduction terms are solved if MU > 0
+    // e.g. 1.0e-3 for Reynolds number 1000 with the nondimensional REFERENCE
+    const MU: f64 = 0.0;
+    const PR: f64 = 0.72;
+    // viscosity law: "constant", "Sutherland" or "power"
+    const VISCOSITY: &str = "Sutherland";
+    // e.g. 273.15 and 110.4 for air
+    const T_REF: f64 = 1.0;
+    // Sutherland temperature
+    const S_SUTH: f64 = 0.3830;
+    // exponent of power law
+    const OMEGA: f64 = 0.76;
+    // axisymmetric flow with y as the radial coordinate
+    // the axis should be at j = NB - 0.5 with "periodical_in_i_with_axis" BC
+    const AXISYMMETRIC: bool = false;
+    // gravitational acceleration
+    const GX: f64 = 0.0;
+    const GY: f64 = 0.0;
+    // preserving isothermal hydrostatic equilibrium under gravity to machine precision
+    // by hydrostatic reconstruction of density and pressure (not at faces of the central flux of hybrid scheme)
+    const WELL_BALANCED: bool = false;
+    // "explicit": unsteady problem by explicit Runge-Kutta
+    // "dual_time": unsteady problem by implicit BDF2 with dual time stepping
+    // "steady": steady problem by implicit LU-SGS with local time stepping
+    const MARCHING: &str = "explicit";
+    // totals of conserved variables, kinetic energy, enstrophy and entropy are written into f_conservation
+    // at the start and every output
+    const CONSERVATION: bool = false;
+    // positivity-preserving limiter of reconstructed face states (Zhang-Shu) with floors of density and pressure
+    // positivity of updates requires a positivity-preserving flux (e.g. "Rusanov") and cfl_coeff <= 0.25 (see README)
+    const LIMIT_FACE: bool = false;
+    const RHO_MIN: f64 = 1.0e-10;
+    const P_MIN: f64 = 1.0e-10;
+    // clipping density and pressure to separate floors after updates of explicit Runge-Kutta stages and LU-SGS
+    // not conservative and not part of the limiter, as a last resort
+    const CLIP: bool = false;
+    const RHO_CLIP: f64 = 1.0e-12;
+    const P_CLIP: f64 = 1.0e-12;
+    // hybrid central/upwind scheme switched by shock sensor ("Ducros" or "Jameson", "none" for central flux everywhere)
+    // the 4th order central flux of split form ("Kennedy_Gruber", "Pirozzoli" or "Ranocha") is used where the sensor is below SENSOR_THRESHOLD,
+    // and the upwind flux of march_rk etc. elsewhere ("none": upwind flux everywhere)
+    // the upwind flux is blended into the central flux by the ratio DISSIPATION (0 for no dissipation)
+    const CENTRAL: &str = "none";
+    const SENSOR: &str = "Ducros";
+    const SENSOR_THRESHOLD: f64 = 0.1;
+    const DISSIPATION: f64 = 0.0;
+    // artificial dissipation added at faces of the central flux ("JST", "VNR", "JST_VNR" or "none"), requiring CENTRAL other than "none"
+    // coefficients of 2nd/4th order dissipation of JST and of artificial bulk viscosity of von Neumann-Richtmyer type
+    const ARTIFICIAL: &str = "none";
+    const K2: f64 = 0.5;
+    const K4: f64 = 1.0 / 64.0;
+    const C_VNR: f64 = 1.0;
+    // shock sensor evaluated from the output basic variables is written into sensor*.dat with them
+    const OUTPUT_SENSOR: bool = false;
 
     let start = std::time::Instant::now();
 
     unsafe {
-        static mut FLUID: fluid2d::IdealGas<NI, NJ, NB> = fluid2d::IdealGas::<NI, NJ, NB>::new(&GAMMA);
-        FLUID.initialize(dir_o, f_coordinate);
+        static mut FLUID: fluid2d::IdealGas<NI, NJ, NB, IdealEoS> = fluid2d::IdealGas::<NI, NJ, NB, IdealEoS>::new(IdealEoS::new(&GAMMA, &(CV / REFERENCE.specific_heat())),
+            TransportProp::new(&REFERENCE.reynolds(&MU), &PR, VISCOSITY, &(T_REF / REFERENCE.temperature), &(S_SUTH / T_REF), &OMEGA),
+            BodyForce::new(&(GX / REFERENCE.acceleration()), &(GY / REFERENCE.acceleration()), &WELL_BALANCED), REFERENCE,
+            PositivityLimiter::new(&LIMIT_FACE, &(RHO_MIN / REFERENCE.density), &(P_MIN / REFERENCE.pressure()),
+                &CLIP, &(RHO_CLIP / REFERENCE.density), &(P_CLIP / REFERENCE.pressure())),
+            HybridScheme::new(CENTRAL, SENSOR, &SENSOR_THRESHOLD, &DISSIPATION),
+            ArtificialDissipation::new(ARTIFICIAL, &K2, &K4, &C_VNR));
+        let fluid = &mut *std::ptr::addr_of_mut!(FLUID);
+        fluid.initialize(dir_o, f_coordinate, &AXISYMMETRIC);
 
-        let t_max = 3.0;
+        // end time in SI units converted into nondimensional one
+        let t_max = 3.0 / REFERENCE.time();
         let n_out = 100;
         let dt_out = t_max / n_out as f64;
         let cfl_coeff = 0.7;
         let mut t = 0.0;
-        FLUID
+        fluid
             .settings
-            .output(f_settings, &t_max, &n_out, &FLUID.eos);
+            .output(f_settings, &t_max, &n_out, &AXISYMMETRIC, &fluid.eos, &fluid.transport, &fluid.force, &fluid.limiter, &fluid.hybrid, &fluid.artificial, &fluid.reference);
+        if CONSERVATION {
+            fluid.output_conservation(f_conservation, &t, &0);
+        }
 
-        for tstep in 1..=n_out {
+        if MARCHING == "steady" {
+            let max_iter = 100000;
+            let n_monitor = 100;
+            // converged when the residual is reduced by res_tol from the initial one
+            let res_tol = 1.0e-8;
+            let cfl_coeff = 10.0;
+            let mut file = BufWriter::new(File::create(f_residual).unwrap());
+            let mut res0 = 0.0;
             let mut iter = 0;
+            while iter < max_iter {
+                iter += 1;
+                let res = fluid.march_lusgs(&cfl_coeff, "periodical_in_i", "MUSCL_minmod_basic", "Roe_FDS");
+                if iter == 1 {
+                    res0 = res;
+                }
+                writeln!(file, "{:7} {:.6e}", iter, res).unwrap();
+                if !res.is_finite() {
+                    panic!("Residual diverged at iter = {}.", iter);
+                }
+                let converged = res < res_tol * res0;
+                if iter % n_monitor == 0 || converged {
+                    println!("iter = {:7} | residual = {:.6e} | reduction = {:.6e}", iter, res, res / res0);
+                }
+                if converged {
+                    break;
+                }
+            }
+            let cpu_time = start.elapsed().as_secs();
+            fluid.basic.output(&fluid.reference, dir_o, f_settings, &0.0, &1, &iter, &None, "", &fluid.limiter.take_counts(), &cpu_time, &0);
+            if CONSERVATION {
+                fluid.output_conservation(f_conservation, &t, &1);
+            }
+        } else {
+            // control of time step
+            // CFL number ramped from cfl_init within n_ramp steps (disabled if n_ramp = 0)
+            let cfl_init = 0.1;
+            let n_ramp = 0;
+            let dt_min = 1.0e-12 / REFERENCE.time();
+            let dt_max = 1.0e+10 / REFERENCE.time();
+            // retrying a step violating positivity with halved time step (disabled if n_retry = 0)
+            let n_retry = 0;
+            let mut ctrl = TimeStepControl::new(&cfl_coeff, &cfl_init, &n_ramp, &dt_min, &dt_max, &n_retry);
+            // for dual time stepping
+            // physical time step may exceed the acoustic CFL limit, and is not limited by the diffusive limit
+            let cfl_phys = 5.0;
+            let cfl_pseudo = 5.0;
+            let n_inner = 30;
+            let inner_tol = 1.0e-3;
+            let mut ctrl_phys = TimeStepControl::new(&cfl_phys, &cfl_init, &n_ramp, &dt_min, &dt_max, &0);
+            // whether diagnostics of eos (e.g. extrapolation of eos table) has been reported
+            let mut eos_warned = false;
 
-            while t < dt_out * tstep as f64 {
-                let dt = FLUID.calc_cfl(&cfl_coeff);
+            for tstep in 1..=n_out {
+                let t_out = dt_out * tstep as f64;
+                let mut iter = 0;
+                let mut n_inner_sum = 0;
+                let mut res_inner_max = 0.0_f64;
 
-                FLUID.march_ssprk3(&dt, "periodical_in_i", "MP5_basic", "Roe_FDS");
+                if MARCHING == "dual_time" {
+                    while t < t_out {
+                        let dt = fluid.calc_dt(&mut ctrl_phys, &t, &t_out, &false);
+                        let (n_inner_done, res_inner) = fluid.march_dual_time(&dt, &cfl_pseudo, &n_inner, &inner_tol,
+                            "periodical_in_i", "MP5_basic", "Roe_FDS");
+                        n_inner_sum += n_inner_done;
+                        res_inner_max = res_inner_max.max(res_inner);
+                        t = if dt == t_out - t { t_out } else { t + dt };
+                        iter += 1;
+                    }
+                } else {
+                    iter = fluid.march_rk_adaptive(&mut ctrl, &mut t, &t_out,
+                        "SSPRK3", "periodical_in_i", "MP5_basic", "Roe_FDS");
+                }
+                let (inner, dt_limit) = if MARCHING == "dual_time" {
+                    (Some((n_inner_sum as f64 / iter as f64, res_inner_max)), ctrl_phys.constraint)
+                } else {
+                    (None, ctrl.constraint)
+                };
 
-                t += dt;
-                iter += 1;
+                let cpu_time = start.elapsed().as_secs();
+                let rest_time = cpu_time * (n_out - tstep) / tstep;
+                fluid.basic.output(
+                    &fluid.reference,
+                    dir_o,
+                    f_settings,
+                    &t,
+                    &(tstep as usize),
+                    &iter,
+                    &inner,
+                    dt_limit,
+                    &fluid.limiter.take_counts(),
+                    &cpu_time,
+                    &rest_time,
+                );
+                if OUTPUT_SENSOR {
+                    fluid.output_sensor(&(dir_o.to_string() + &format!("sensor{:07}.dat", tstep)));
+                }
+                if CONSERVATION {
+                    fluid.output_conservation(f_conservation, &t, &(tstep as usize));
+                }
+                if !eos_warned {
+                    let diagnostics = fluid.eos.diagnostics();
+                    if !diagnostics.is_empty() {
+                        println!("{}", diagnostics);
+                        eos_warned = true;
+                    }
+                }
             }
-
-            let cpu_time = start.elapsed().as_secs();
-            let rest_time = cpu_time * (n_out - tstep) / tstep;
-            FLUID.basic.output(
-                dir_o,
-                f_settings,
-                &t,
-                &(tstep as usize),
-                &iter,
-                &cpu_time,
-                &rest_time,
-            );
+        }
+        let diagnostics = fluid.eos.diagnostics();
+        if !diagnostics.is_empty() {
+            println!("{}", diagnostics);
         }
     } // unsafe
 